@@ -0,0 +1,548 @@
+use crate::models::{Category, ExpenseCategory, IncomeCategory};
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_LEDGER_PATH: &str = "store/data.json";
+const PROJECT_CONFIG_FILE: &str = "kakeibo.toml";
+const USER_CONFIG_DIR: &str = "kakeibo";
+const USER_CONFIG_FILE: &str = "config.toml";
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub ledger_path: String,
+    pub default_payment_method: Option<String>,
+    pub fiscal_year_start: u32,
+    pub currency: CurrencyFormat,
+    pub output_format: OutputFormat,
+    pub enabled_categories: EnabledCategories,
+    pub backup: BackupConfig,
+    pub git: GitConfig,
+    pub duplicate: DuplicateConfig,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CurrencyFormat {
+    pub symbol: String,
+    pub symbol_position: SymbolPosition,
+    pub thousands_separator: bool,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolPosition {
+    Prefix,
+    Suffix,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnabledCategories {
+    pub income: Vec<IncomeCategory>,
+    pub expense: Vec<ExpenseCategory>,
+}
+
+// スナップショットの保持数。keep_lastに加えて日・週・月ごとに最新の1件を残す
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupConfig {
+    pub enabled: bool,
+    pub dir: Option<String>,
+    pub keep_last: usize,
+    pub keep_daily: usize,
+    pub keep_weekly: usize,
+    pub keep_monthly: usize,
+}
+
+// 有効にすると家計簿ディレクトリをgitリポジトリとして書き込みごとにコミットする
+#[derive(Deserialize, Debug, PartialEq, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct GitConfig {
+    pub enabled: bool,
+}
+
+// 取り込み・登録時の重複判定。日付がwindow_days日以内で金額が同じ、品目名が似ている項目を重複とみなす
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct DuplicateConfig {
+    pub window_days: u32,
+    pub action: DuplicateAction,
+}
+
+// 重複の可能性がある取り込み項目の扱い
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateAction {
+    Skip,
+    Merge,
+    Keep,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            ledger_path: DEFAULT_LEDGER_PATH.to_string(),
+            default_payment_method: None,
+            fiscal_year_start: 1,
+            currency: CurrencyFormat::default(),
+            output_format: OutputFormat::Text,
+            enabled_categories: EnabledCategories::default(),
+            backup: BackupConfig::default(),
+            git: GitConfig::default(),
+            duplicate: DuplicateConfig::default(),
+        }
+    }
+}
+
+impl Default for DuplicateConfig {
+    fn default() -> Self {
+        DuplicateConfig {
+            window_days: 2,
+            action: DuplicateAction::Skip,
+        }
+    }
+}
+
+impl Default for CurrencyFormat {
+    fn default() -> Self {
+        CurrencyFormat {
+            symbol: "円".to_string(),
+            symbol_position: SymbolPosition::Suffix,
+            thousands_separator: false,
+        }
+    }
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        BackupConfig {
+            enabled: true,
+            dir: None,
+            keep_last: 10,
+            keep_daily: 7,
+            keep_weekly: 4,
+            keep_monthly: 12,
+        }
+    }
+}
+
+impl Default for EnabledCategories {
+    fn default() -> Self {
+        EnabledCategories {
+            income: IncomeCategory::ALL.to_vec(),
+            expense: ExpenseCategory::ALL.to_vec(),
+        }
+    }
+}
+
+impl Config {
+    // ユーザー設定(XDG)→プロジェクト設定→環境変数の順に上書きする
+    pub fn load() -> Self {
+        let mut table = toml::Table::new();
+        let paths = [user_config_path(), Some(PathBuf::from(PROJECT_CONFIG_FILE))];
+        for path in paths.iter().flatten() {
+            if path.exists() {
+                merge_table(&mut table, read_table(path));
+            }
+        }
+        let mut config = Self::from_table(table);
+        config.apply_env(|key| env::var(key).ok());
+        config.validate();
+        config
+    }
+
+    fn from_table(table: toml::Table) -> Self {
+        table.try_into().expect("設定ファイルの形式が不正です")
+    }
+
+    fn apply_env<F: Fn(&str) -> Option<String>>(&mut self, get_env: F) {
+        if let Some(value) = get_env("KAKEIBO_LEDGER_PATH") {
+            self.ledger_path = value;
+        }
+        if let Some(value) = get_env("KAKEIBO_DEFAULT_PAYMENT_METHOD") {
+            self.default_payment_method = Some(value).filter(|value| !value.is_empty());
+        }
+        if let Some(value) = get_env("KAKEIBO_FISCAL_YEAR_START") {
+            self.fiscal_year_start = value
+                .parse()
+                .expect("KAKEIBO_FISCAL_YEAR_STARTは数値で指定してください");
+        }
+        if let Some(value) = get_env("KAKEIBO_CURRENCY_SYMBOL") {
+            self.currency.symbol = value;
+        }
+        if let Some(value) = get_env("KAKEIBO_OUTPUT_FORMAT") {
+            self.output_format = parse_env_value(&value, "KAKEIBO_OUTPUT_FORMAT");
+        }
+        if let Some(value) = get_env("KAKEIBO_ENABLED_INCOME_CATEGORIES") {
+            self.enabled_categories.income =
+                parse_env_list(&value, "KAKEIBO_ENABLED_INCOME_CATEGORIES");
+        }
+        if let Some(value) = get_env("KAKEIBO_ENABLED_EXPENSE_CATEGORIES") {
+            self.enabled_categories.expense =
+                parse_env_list(&value, "KAKEIBO_ENABLED_EXPENSE_CATEGORIES");
+        }
+        if let Some(value) = get_env("KAKEIBO_BACKUP_ENABLED") {
+            self.backup.enabled = value
+                .parse()
+                .expect("KAKEIBO_BACKUP_ENABLEDはtrueまたはfalseで指定してください");
+        }
+        if let Some(value) = get_env("KAKEIBO_GIT_ENABLED") {
+            self.git.enabled = value
+                .parse()
+                .expect("KAKEIBO_GIT_ENABLEDはtrueまたはfalseで指定してください");
+        }
+        if let Some(value) = get_env("KAKEIBO_BACKUP_DIR") {
+            self.backup.dir = Some(value).filter(|value| !value.is_empty());
+        }
+    }
+
+    fn validate(&self) {
+        if !(1..=12).contains(&self.fiscal_year_start) {
+            panic!("fiscal_year_startは1から12の範囲で指定してください");
+        }
+    }
+
+    pub fn get_fiscal_year(&self, date: NaiveDate) -> i32 {
+        if date.month() >= self.fiscal_year_start {
+            date.year()
+        } else {
+            date.year() - 1
+        }
+    }
+
+    pub fn get_year_label(&self, fiscal_year: i32) -> String {
+        if self.fiscal_year_start == 1 {
+            format!("{}年", fiscal_year)
+        } else {
+            format!("{}年度", fiscal_year)
+        }
+    }
+}
+
+impl CurrencyFormat {
+    pub fn format(&self, price: i64) -> String {
+        let digits = price.unsigned_abs().to_string();
+        let digits = if self.thousands_separator {
+            insert_thousands_separator(&digits)
+        } else {
+            digits
+        };
+        let sign = if price < 0 { "-" } else { "" };
+        match self.symbol_position {
+            SymbolPosition::Prefix => format!("{}{}{}", sign, self.symbol, digits),
+            SymbolPosition::Suffix => format!("{}{}{}", sign, digits, self.symbol),
+        }
+    }
+}
+
+impl BackupConfig {
+    // 未指定の場合は家計簿ファイルと同じディレクトリのbackupsに保存する
+    pub fn get_dir(&self, file_path: &str) -> PathBuf {
+        match &self.dir {
+            Some(dir) => PathBuf::from(dir),
+            None => Path::new(file_path)
+                .parent()
+                .unwrap_or(Path::new(""))
+                .join("backups"),
+        }
+    }
+}
+
+impl OutputFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+impl DuplicateAction {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "skip" => Some(DuplicateAction::Skip),
+            "merge" => Some(DuplicateAction::Merge),
+            "keep" => Some(DuplicateAction::Keep),
+            _ => None,
+        }
+    }
+}
+
+impl EnabledCategories {
+    pub fn contains(&self, category: &Category) -> bool {
+        match category {
+            Category::Income(category) => self.income.contains(category),
+            Category::Expense(category) => self.expense.contains(category),
+        }
+    }
+}
+
+// $XDG_CONFIG_HOME/kakeibo(未設定の場合は ~/.config/kakeibo)
+pub fn get_user_config_dir() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_home.join(USER_CONFIG_DIR))
+}
+
+fn user_config_path() -> Option<PathBuf> {
+    Some(get_user_config_dir()?.join(USER_CONFIG_FILE))
+}
+
+fn read_table(path: &Path) -> toml::Table {
+    let content = fs::read_to_string(path).expect("設定ファイルの読み込みに失敗しました");
+    content
+        .parse()
+        .unwrap_or_else(|e| panic!("設定ファイルの形式が不正です({}): {}", path.display(), e))
+}
+
+// ネストしたテーブルはキー単位で上書きする
+fn merge_table(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                merge_table(base_table, overlay_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn parse_env_value<T: for<'de> Deserialize<'de>>(value: &str, key: &str) -> T {
+    T::deserialize(toml::Value::String(value.trim().to_string()))
+        .unwrap_or_else(|_| panic!("{}の値が不正です: {}", key, value))
+}
+
+fn parse_env_list<T: for<'de> Deserialize<'de>>(value: &str, key: &str) -> Vec<T> {
+    value
+        .split(',')
+        .filter(|element| !element.trim().is_empty())
+        .map(|element| parse_env_value(element, key))
+        .collect()
+}
+
+fn insert_thousands_separator(digits: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            result.push(',');
+        }
+        result.push(c);
+    }
+    result
+}
+
+#[cfg(test)]
+mod config_test {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn parse(content: &str) -> Config {
+        Config::from_table(content.parse().unwrap())
+    }
+
+    #[test]
+    fn test_default() {
+        let config = parse("");
+        assert_eq!(config, Config::default());
+        assert_eq!(config.ledger_path, "store/data.json");
+        assert_eq!(config.fiscal_year_start, 1);
+    }
+
+    #[test]
+    fn test_from_table() {
+        let config = parse(
+            r#"
+            ledger_path = "ledger/kakeibo.json"
+            default_payment_method = "現金"
+            fiscal_year_start = 4
+            output_format = "text"
+
+            [currency]
+            symbol = "¥"
+            symbol_position = "prefix"
+            thousands_separator = true
+
+            [enabled_categories]
+            expense = ["Food", "Other"]
+            "#,
+        );
+        assert_eq!(config.ledger_path, "ledger/kakeibo.json");
+        assert_eq!(config.default_payment_method, Some("現金".to_string()));
+        assert_eq!(config.fiscal_year_start, 4);
+        assert_eq!(config.currency.symbol_position, SymbolPosition::Prefix);
+        assert_eq!(
+            config.enabled_categories.income,
+            IncomeCategory::ALL.to_vec()
+        );
+        assert_eq!(
+            config.enabled_categories.expense,
+            vec![ExpenseCategory::Food, ExpenseCategory::Other]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "設定ファイルの形式が不正です")]
+    fn test_from_table_unknown_key() {
+        parse("ledger = \"store/data.json\"");
+    }
+
+    #[test]
+    fn test_merge_table() {
+        let mut base: toml::Table =
+            "ledger_path = \"a.json\"\n[currency]\nsymbol = \"円\"\nthousands_separator = true"
+                .parse()
+                .unwrap();
+        let overlay: toml::Table = "[currency]\nsymbol = \"¥\"".parse().unwrap();
+        merge_table(&mut base, overlay);
+
+        let config = Config::from_table(base);
+        assert_eq!(config.ledger_path, "a.json");
+        assert_eq!(config.currency.symbol, "¥");
+        assert!(config.currency.thousands_separator);
+    }
+
+    #[test]
+    fn test_apply_env() {
+        let env: HashMap<&str, &str> = [
+            ("KAKEIBO_LEDGER_PATH", "/tmp/ci.json"),
+            ("KAKEIBO_FISCAL_YEAR_START", "4"),
+            ("KAKEIBO_OUTPUT_FORMAT", "text"),
+            ("KAKEIBO_ENABLED_EXPENSE_CATEGORIES", "Food, Hobby"),
+        ]
+        .into_iter()
+        .collect();
+        let mut config = Config::default();
+        config.apply_env(|key| env.get(key).map(|value| value.to_string()));
+
+        assert_eq!(config.ledger_path, "/tmp/ci.json");
+        assert_eq!(config.fiscal_year_start, 4);
+        assert_eq!(
+            config.enabled_categories.expense,
+            vec![ExpenseCategory::Food, ExpenseCategory::Hobby]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "KAKEIBO_ENABLED_INCOME_CATEGORIESの値が不正です")]
+    fn test_apply_env_invalid_category() {
+        let mut config = Config::default();
+        config.apply_env(|key| {
+            (key == "KAKEIBO_ENABLED_INCOME_CATEGORIES").then(|| "Food".to_string())
+        });
+    }
+
+    #[test]
+    fn test_backup_config() {
+        let config = parse("[backup]\nkeep_last = 3\nkeep_monthly = 0");
+        assert!(config.backup.enabled);
+        assert_eq!(config.backup.keep_last, 3);
+        assert_eq!(config.backup.keep_daily, 7);
+        assert_eq!(config.backup.keep_monthly, 0);
+        assert_eq!(
+            config.backup.get_dir("store/data.json"),
+            PathBuf::from("store/backups")
+        );
+
+        let mut config = Config::default();
+        config.apply_env(|key| match key {
+            "KAKEIBO_BACKUP_ENABLED" => Some("false".to_string()),
+            "KAKEIBO_BACKUP_DIR" => Some("/tmp/kakeibo-backups".to_string()),
+            _ => None,
+        });
+        assert!(!config.backup.enabled);
+        assert_eq!(
+            config.backup.get_dir("store/data.json"),
+            PathBuf::from("/tmp/kakeibo-backups")
+        );
+    }
+
+    #[test]
+    fn test_git_config() {
+        assert!(!Config::default().git.enabled);
+        assert!(parse("[git]\nenabled = true").git.enabled);
+
+        let mut config = Config::default();
+        config.apply_env(|key| (key == "KAKEIBO_GIT_ENABLED").then(|| "true".to_string()));
+        assert!(config.git.enabled);
+    }
+
+    #[test]
+    fn test_duplicate_config() {
+        let config = parse("[duplicate]\nwindow_days = 5\naction = \"merge\"");
+        assert_eq!(config.duplicate.window_days, 5);
+        assert_eq!(config.duplicate.action, DuplicateAction::Merge);
+        assert_eq!(Config::default().duplicate.action, DuplicateAction::Skip);
+        assert_eq!(
+            DuplicateAction::from_name("keep"),
+            Some(DuplicateAction::Keep)
+        );
+        assert_eq!(DuplicateAction::from_name("both"), None);
+    }
+
+    #[test]
+    fn test_get_fiscal_year() {
+        let mut config = Config::default();
+        let date = NaiveDate::from_ymd_opt(2025, 3, 31).unwrap();
+        assert_eq!(config.get_fiscal_year(date), 2025);
+        assert_eq!(config.get_year_label(2025), "2025年");
+
+        config.fiscal_year_start = 4;
+        assert_eq!(config.get_fiscal_year(date), 2024);
+        assert_eq!(
+            config.get_fiscal_year(NaiveDate::from_ymd_opt(2025, 4, 1).unwrap()),
+            2025
+        );
+        assert_eq!(config.get_year_label(2024), "2024年度");
+    }
+
+    #[test]
+    #[should_panic(expected = "fiscal_year_startは1から12の範囲で指定してください")]
+    fn test_validate_fiscal_year_start() {
+        parse("fiscal_year_start = 13").validate();
+    }
+
+    #[test]
+    fn test_currency_format() {
+        let mut currency = CurrencyFormat::default();
+        assert_eq!(currency.format(1000), "1000円");
+        assert_eq!(currency.format(-1000), "-1000円");
+
+        currency.thousands_separator = true;
+        assert_eq!(currency.format(1234567), "1,234,567円");
+        assert_eq!(currency.format(123), "123円");
+        // i32に収まらない残高も桁あふれせずに表示する
+        assert_eq!(currency.format(3_000_000_000), "3,000,000,000円");
+
+        currency.symbol = "¥".to_string();
+        currency.symbol_position = SymbolPosition::Prefix;
+        assert_eq!(currency.format(-800), "-¥800");
+        assert_eq!(currency.format(0), "¥0");
+    }
+
+    #[test]
+    fn test_enabled_categories_contains() {
+        let enabled = EnabledCategories {
+            income: vec![IncomeCategory::Salary],
+            expense: vec![],
+        };
+        assert!(enabled.contains(&Category::Income(IncomeCategory::Salary)));
+        assert!(!enabled.contains(&Category::Income(IncomeCategory::Bonus)));
+        assert!(!enabled.contains(&Category::Expense(ExpenseCategory::Food)));
+    }
+}
@@ -1,9 +1,23 @@
-use kakeibo_app::services;
+use chrono::{Datelike, Local, NaiveDate};
+use kakeibo_app::{config, services};
+use std::collections::{HashMap, HashSet};
+use std::env;
 use std::io;
 
-const FILE_PATH: &str = "store/data.json";
-
 fn main() {
+    let config = config::Config::load();
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.is_empty() {
+        // 対話モードの起動時だけ期日が来た定期的な項目を登録する。
+        // コマンドでは出力(JSON・CSV)や--dry-runを汚さないよう`recurring apply`で明示的に登録する
+        services::recurring::apply_due(&config, Local::now().date_naive());
+        run_interactive(&config);
+    } else {
+        run_command(&args, &config);
+    }
+}
+
+fn run_interactive(config: &config::Config) {
     let mut service_type = String::new();
 
     println!("実行したい内容を入力してください(0: 登録, 1: 集計, 2: 統計)");
@@ -14,11 +28,252 @@ fn main() {
     services::validate::InputValidator::validate_service_type(service_type);
 
     if service_type == 0 {
-        services::register::run(FILE_PATH);
+        services::register::run(config);
     } else if service_type == 1 {
-        services::summarize::run(FILE_PATH);
+        services::summarize::run(
+            config,
+            config.output_format,
+            services::summarize::SummaryBasis::Usage,
+        );
     } else if service_type == 2 {
-        services::statistics::run(FILE_PATH);
+        services::statistics::run(config, config.output_format);
+    }
+}
+
+// 値を取るオプション。それ以外の"--"で始まる引数はフラグとして扱う
+const VALUE_OPTIONS: [&str; 24] = [
+    "--account",
+    "--balance",
+    "--basis",
+    "--category",
+    "--closing-day",
+    "--date",
+    "--encoding",
+    "--end",
+    "--file",
+    "--format",
+    "--html",
+    "--kind",
+    "--mapping",
+    "--memo",
+    "--month",
+    "--on-duplicate",
+    "--output",
+    "--payment-account",
+    "--payment-day",
+    "--pdf",
+    "--preset",
+    "--schedule",
+    "--start",
+    "--year",
+];
+
+struct CommandArgs<'a> {
+    positional: Vec<&'a str>,
+    options: HashMap<&'a str, &'a str>,
+    flags: HashSet<&'a str>,
+}
+
+impl<'a> CommandArgs<'a> {
+    fn parse(args: &'a [String]) -> Self {
+        let mut command_args = CommandArgs {
+            positional: Vec::new(),
+            options: HashMap::new(),
+            flags: HashSet::new(),
+        };
+        let mut iter = args.iter().map(|arg| arg.as_str());
+        while let Some(arg) = iter.next() {
+            if VALUE_OPTIONS.contains(&arg) {
+                let value = iter
+                    .next()
+                    .unwrap_or_else(|| panic!("{}の値を指定してください", arg));
+                command_args.options.insert(arg, value);
+            } else if arg.starts_with("--") {
+                command_args.flags.insert(arg);
+            } else {
+                command_args.positional.push(arg);
+            }
+        }
+        command_args
+    }
+
+    fn get_option(&self, name: &str) -> Option<&'a str> {
+        self.options.get(name).copied()
+    }
+
+    fn require_option(&self, name: &str) -> &'a str {
+        self.get_option(name)
+            .unwrap_or_else(|| panic!("{}を指定してください", name))
+    }
+
+    fn has_flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+
+    // --outputを省略した場合は設定のoutput_formatに従う
+    fn get_output_format(&self, config: &config::Config) -> config::OutputFormat {
+        match self.get_option("--output") {
+            Some(format) => config::OutputFormat::from_name(format)
+                .unwrap_or_else(|| panic!("--outputの値が不正です: {}", format)),
+            None => config.output_format,
+        }
+    }
+
+    // --monthを省略した場合は今月
+    fn get_month(&self) -> NaiveDate {
+        match self.get_option("--month") {
+            Some(month) => services::report::markdown::parse_month(month),
+            None => Local::now().date_naive().with_day(1).unwrap(),
+        }
+    }
+
+    fn get_import_options(&self) -> services::import::ImportOptions {
+        services::import::ImportOptions {
+            dry_run: self.has_flag("--dry-run"),
+            assume_yes: self.has_flag("--yes"),
+            on_duplicate: self.get_option("--on-duplicate").map(|action| {
+                config::DuplicateAction::from_name(action)
+                    .unwrap_or_else(|| panic!("--on-duplicateの値が不正です: {}", action))
+            }),
+            account: self
+                .get_option("--account")
+                .map(|account| account.to_string()),
+        }
+    }
+
+    fn get_export_options(&self, target: &str) -> services::export::ExportOptions {
+        let format = self.get_option("--format").unwrap_or("csv");
+        let encoding = self.get_option("--encoding").unwrap_or("utf8");
+        services::export::ExportOptions {
+            format: services::export::ExportFormat::from_name(format)
+                .unwrap_or_else(|| panic!("--formatの値が不正です: {}", format)),
+            target: services::export::ExportTarget::from_name(target)
+                .unwrap_or_else(|| panic!("出力対象が不正です: {}", target)),
+            encoding: services::io::TextEncoding::from_name(encoding)
+                .unwrap_or_else(|| panic!("--encodingの値が不正です: {}", encoding)),
+            file: self.get_option("--file").map(|file| file.to_string()),
+        }
+    }
+}
+
+fn run_command(args: &[String], config: &config::Config) {
+    let args = CommandArgs::parse(args);
+    match args.positional.as_slice() {
+        ["backup", "list"] => services::backup::run_list(config),
+        ["backup", "restore", snapshot] => services::backup::run_restore(config, snapshot),
+        ["archive", "list"] => services::archive::run_list(config),
+        ["archive", year] => services::archive::run(config, year),
+        ["summarize"] => {
+            let basis = args.get_option("--basis").unwrap_or("usage");
+            let basis = services::summarize::SummaryBasis::from_name(basis)
+                .unwrap_or_else(|| panic!("--basisの値が不正です: {}", basis));
+            services::summarize::run(config, args.get_output_format(config), basis);
+        }
+        ["statistics"] => services::statistics::run(config, args.get_output_format(config)),
+        ["budget"] => {
+            services::budget::run(config, args.get_month(), args.get_output_format(config))
+        }
+        ["budget", "set", category, amount] => services::budget::run_set(config, category, amount),
+        ["envelopes"] => {
+            services::envelope::run(config, args.get_month(), args.get_output_format(config))
+        }
+        ["envelopes", "assign", category, amount] => {
+            services::envelope::run_assign(config, args.get_month(), category, amount)
+        }
+        ["recurring"] | ["recurring", "list"] => services::recurring::run_list(config),
+        ["recurring", "add", name, amount] => services::recurring::run_add(
+            config,
+            name,
+            amount,
+            args.get_option("--category"),
+            args.require_option("--schedule"),
+            args.get_option("--start"),
+            args.get_option("--end"),
+        ),
+        ["recurring", "skip", id, date] => services::recurring::run_skip(config, id, date),
+        ["recurring", "adjust", id, date, price] => {
+            services::recurring::run_adjust(config, id, date, price)
+        }
+        ["recurring", "apply"] => services::recurring::run_apply(config),
+        ["accounts"] => services::account::run_list(config, args.get_output_format(config)),
+        ["accounts", "add", name] => services::account::run_add(
+            config,
+            name,
+            args.require_option("--kind"),
+            args.get_option("--balance"),
+            args.get_option("--date"),
+            services::account::parse_billing(
+                args.get_option("--closing-day"),
+                args.get_option("--payment-day"),
+                args.get_option("--payment-account"),
+            ),
+        ),
+        ["card", "statement", name] => services::card::run_statement(
+            config,
+            name,
+            args.get_option("--month")
+                .map(services::report::markdown::parse_month),
+        ),
+        ["card", "cashflow"] => services::card::run_cashflow(config, args.get_month()),
+        ["accounts", "statement", name] => {
+            services::account::run_statement(config, name, args.get_month())
+        }
+        ["transfer", from, to, amount] => services::transfer::run(
+            config,
+            from,
+            to,
+            amount,
+            args.get_option("--date"),
+            args.get_option("--memo"),
+        ),
+        ["reconcile", name, balance] => {
+            services::reconcile::run(config, name, balance, args.get_option("--date"))
+        }
+        ["transfers"] => services::transfer::run_list(config, args.get_month()),
+        ["history"] => services::git::run_history(config),
+        ["check"] => {
+            let mode = if args.has_flag("--auto") {
+                services::check::FixMode::Auto
+            } else if args.has_flag("--fix") {
+                services::check::FixMode::Interactive
+            } else {
+                services::check::FixMode::Report
+            };
+            services::check::run(config, mode);
+        }
+        ["import", "ofx", file] => {
+            services::import::ofx::run(config, file, args.get_import_options())
+        }
+        ["import", "journal", file] => {
+            services::import::journal::run(config, file, args.get_import_options())
+        }
+        ["import", "qif", file] => {
+            services::import::qif::run(config, file, args.get_import_options())
+        }
+        ["import", "presets"] => services::import::preset::run_list(),
+        ["import", "csv", file] => {
+            let mapping = match args.get_option("--preset") {
+                Some(preset) => services::import::preset::load(preset),
+                None => {
+                    services::import::csv::CsvMapping::from_file(args.require_option("--mapping"))
+                }
+            };
+            services::import::csv::run(config, file, &mapping, args.get_import_options());
+        }
+        ["export"] => services::export::run(config, &args.get_export_options("items")),
+        ["export", target] => services::export::run(config, &args.get_export_options(target)),
+        ["report"] if args.has_flag("--markdown") => {
+            services::report::run_markdown(config, args.get_month(), args.get_option("--file"));
+        }
+        ["report"] if args.get_option("--pdf").is_some() => {
+            let year = match args.get_option("--year") {
+                Some(year) => year.parse().expect("--yearは数値で指定してください"),
+                None => config.get_fiscal_year(Local::now().date_naive()),
+            };
+            services::report::run_pdf(config, year, args.require_option("--pdf"));
+        }
+        ["report"] => services::report::run_html(config, args.require_option("--html")),
+        _ => panic!("不正なコマンドです"),
     }
 }
 
@@ -28,9 +283,12 @@ mod main_test {
 
     #[test]
     fn test_main_function_structure() {
-        // main関数の構造をテスト
-        // 実際のテストでは標準入出力をリダイレクトする必要があります
-        assert!(true); // ダミーアサーション
+        // 引数がない場合(対話モード)はコマンドとして何も解釈しない
+        // 対話モード自体は標準入出力をリダイレクトする必要があるためテストしない
+        let args = CommandArgs::parse(&[]);
+        assert!(args.positional.is_empty());
+        assert!(args.options.is_empty());
+        assert!(args.flags.is_empty());
     }
 
     #[test]
@@ -46,4 +304,40 @@ mod main_test {
     fn test_service_type_validation_panic() {
         services::validate::InputValidator::validate_service_type(3);
     }
+
+    #[test]
+    fn test_command_args_parse() {
+        let args: Vec<String> = ["import", "csv", "a.csv", "--mapping", "m.toml", "--dry-run"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let args = CommandArgs::parse(&args);
+        assert_eq!(args.positional, vec!["import", "csv", "a.csv"]);
+        assert_eq!(args.get_option("--mapping"), Some("m.toml"));
+        assert_eq!(args.get_option("--preset"), None);
+        assert!(args.has_flag("--dry-run"));
+        assert!(!args.get_import_options().assume_yes);
+        assert_eq!(args.get_import_options().on_duplicate, None);
+
+        let args: Vec<String> = ["--on-duplicate", "merge"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(
+            CommandArgs::parse(&args).get_import_options().on_duplicate,
+            Some(config::DuplicateAction::Merge)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "--mappingの値を指定してください")]
+    fn test_command_args_parse_missing_value() {
+        CommandArgs::parse(&["--mapping".to_string()]);
+    }
+
+    #[test]
+    #[should_panic(expected = "不正なコマンドです")]
+    fn test_run_command_unknown() {
+        run_command(&["unknown".to_string()], &config::Config::default());
+    }
 }
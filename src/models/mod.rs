@@ -1,44 +1,162 @@
 use chrono::{Datelike, NaiveDate};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum IncomeCategory {
     Salary,
     Bonus,
     Other,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum ExpenseCategory {
     Food,
     Hobby,
     Other,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum Category {
     Income(IncomeCategory),
     Expense(ExpenseCategory),
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct Item {
+    #[serde(default)]
+    id: u32,
     name: String,
     category: Category,
     price: u32,
     date: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    payment_method: Option<String>,
+    // 入出金した口座(accounts.jsonの名前)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    account: Option<String>,
+    // 取り込み元の取引ID(OFXのFITIDなど)。重複の判定に使う
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    external_id: Option<String>,
+    // 銀行などの明細と照合済み
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    cleared: bool,
+    // カテゴリーごとの内訳。空でなければカテゴリー別の集計はcategoryではなく内訳で行う
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    splits: Vec<Split>,
+}
+
+// 1つの項目を複数のカテゴリーに分けた内訳の1行。金額の合計は項目の金額と一致する
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Split {
+    category: Category,
+    price: u32,
+}
+
+impl IncomeCategory {
+    pub const ALL: [IncomeCategory; 3] = [
+        IncomeCategory::Salary,
+        IncomeCategory::Bonus,
+        IncomeCategory::Other,
+    ];
+
+    // 列挙子名(Salary)と表示名(給与)のどちらでも受け付ける
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|category| format!("{:?}", category) == name || category.get_label() == name)
+    }
+
+    pub fn get_label(&self) -> &str {
+        match self {
+            IncomeCategory::Salary => "給与",
+            IncomeCategory::Bonus => "ボーナス",
+            IncomeCategory::Other => "その他",
+        }
+    }
+}
+
+impl ExpenseCategory {
+    pub const ALL: [ExpenseCategory; 3] = [
+        ExpenseCategory::Food,
+        ExpenseCategory::Hobby,
+        ExpenseCategory::Other,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|category| format!("{:?}", category) == name || category.get_label() == name)
+    }
+
+    pub fn get_label(&self) -> &str {
+        match self {
+            ExpenseCategory::Food => "食費",
+            ExpenseCategory::Hobby => "趣味",
+            ExpenseCategory::Other => "その他",
+        }
+    }
+}
+
+impl Category {
+    pub fn get_label(&self) -> &str {
+        match self {
+            Category::Income(category) => category.get_label(),
+            Category::Expense(category) => category.get_label(),
+        }
+    }
 }
 
 impl Item {
     pub fn new(name: String, category: Category, price: u32, date: NaiveDate) -> Self {
         Item {
+            id: 0,
             name,
             category,
             price,
             date,
+            payment_method: None,
+            account: None,
+            external_id: None,
+            cleared: false,
+            splits: Vec::new(),
         }
     }
 
+    pub fn with_id(mut self, id: u32) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn with_payment_method(mut self, payment_method: Option<String>) -> Self {
+        self.payment_method = payment_method;
+        self
+    }
+
+    pub fn with_account(mut self, account: Option<String>) -> Self {
+        self.account = account;
+        self
+    }
+
+    pub fn with_splits(mut self, splits: Vec<Split>) -> Self {
+        self.splits = splits;
+        self
+    }
+
+    pub fn with_cleared(mut self, cleared: bool) -> Self {
+        self.cleared = cleared;
+        self
+    }
+
+    pub fn with_date(mut self, date: NaiveDate) -> Self {
+        self.date = date;
+        self
+    }
+
+    pub fn with_external_id(mut self, external_id: Option<String>) -> Self {
+        self.external_id = external_id;
+        self
+    }
+
     pub fn get_category(register_type: u8, category_type: u8) -> Category {
         if register_type == 0 {
             match category_type {
@@ -65,22 +183,84 @@ impl Item {
         self.date.month()
     }
 
+    pub fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+
     pub fn get_first_day(&self) -> NaiveDate {
         NaiveDate::from_ymd_opt(self.get_year(), self.get_month(), 1).unwrap()
     }
 
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
     pub fn get_name(&self) -> &str {
         &self.name
     }
 
+    pub fn get_item_category(&self) -> &Category {
+        &self.category
+    }
+
+    pub fn get_price(&self) -> u32 {
+        self.price
+    }
+
+    pub fn get_payment_method(&self) -> Option<&str> {
+        self.payment_method.as_deref()
+    }
+
+    pub fn get_account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    pub fn get_splits(&self) -> &[Split] {
+        &self.splits
+    }
+
+    // カテゴリー別の集計に使うカテゴリーと金額。内訳がなければ項目のカテゴリーと金額
+    pub fn get_category_prices(&self) -> Vec<(Category, u32)> {
+        if self.splits.is_empty() {
+            vec![(self.category, self.price)]
+        } else {
+            self.splits
+                .iter()
+                .map(|split| (split.category, split.price))
+                .collect()
+        }
+    }
+
+    pub fn is_cleared(&self) -> bool {
+        self.cleared
+    }
+
+    pub fn get_external_id(&self) -> Option<&str> {
+        self.external_id.as_deref()
+    }
+
     pub fn get_price_for_summary(&self) -> i32 {
         match self.category {
             Category::Income(_) => self.price as i32,
-            Category::Expense(_) => -1 * self.price as i32,
+            Category::Expense(_) => -(self.price as i32),
         }
     }
 }
 
+impl Split {
+    pub fn new(category: Category, price: u32) -> Self {
+        Split { category, price }
+    }
+
+    pub fn get_category(&self) -> &Category {
+        &self.category
+    }
+
+    pub fn get_price(&self) -> u32 {
+        self.price
+    }
+}
+
 #[cfg(test)]
 mod models_test {
     use super::*;
@@ -198,4 +378,67 @@ mod models_test {
         );
         assert_eq!(item.get_name(), "テスト商品");
     }
+
+    #[test]
+    fn test_get_label() {
+        assert_eq!(Category::Income(IncomeCategory::Salary).get_label(), "給与");
+        assert_eq!(Category::Expense(ExpenseCategory::Food).get_label(), "食費");
+        assert_eq!(
+            Category::Expense(ExpenseCategory::Other).get_label(),
+            "その他"
+        );
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(
+            IncomeCategory::from_name("Bonus"),
+            Some(IncomeCategory::Bonus)
+        );
+        assert_eq!(
+            IncomeCategory::from_name("給与"),
+            Some(IncomeCategory::Salary)
+        );
+        assert_eq!(IncomeCategory::from_name("食費"), None);
+        assert_eq!(
+            ExpenseCategory::from_name("食費"),
+            Some(ExpenseCategory::Food)
+        );
+        assert_eq!(
+            ExpenseCategory::from_name("その他"),
+            Some(ExpenseCategory::Other)
+        );
+        assert_eq!(ExpenseCategory::from_name("Travel"), None);
+    }
+
+    #[test]
+    fn test_payment_method() {
+        let item = Item::new(
+            "ランチ".to_string(),
+            Category::Expense(ExpenseCategory::Food),
+            800,
+            NaiveDate::from_ymd_opt(2025, 8, 1).unwrap(),
+        );
+        assert_eq!(item.get_payment_method(), None);
+
+        let item = item.with_payment_method(Some("現金".to_string()));
+        assert_eq!(item.get_payment_method(), Some("現金"));
+    }
+
+    #[test]
+    fn test_get_category_prices() {
+        let food = Category::Expense(ExpenseCategory::Food);
+        let hobby = Category::Expense(ExpenseCategory::Hobby);
+        let item = Item::new(
+            "スーパー".to_string(),
+            food,
+            3000,
+            NaiveDate::from_ymd_opt(2025, 8, 1).unwrap(),
+        );
+        assert_eq!(item.get_category_prices(), vec![(food, 3000)]);
+
+        let item = item.with_splits(vec![Split::new(food, 2200), Split::new(hobby, 800)]);
+        assert_eq!(item.get_category_prices(), vec![(food, 2200), (hobby, 800)]);
+        assert_eq!(item.get_price_for_summary(), -3000);
+    }
 }
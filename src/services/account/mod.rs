@@ -0,0 +1,590 @@
+use crate::config::{Config, OutputFormat};
+use crate::services::transfer::Transfer;
+use crate::{models, services};
+use chrono::{Datelike, Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+pub const ACCOUNT_FILE: &str = "accounts.json";
+
+// 現金・銀行口座・クレジットカードなどの口座。opening_dateより前の項目は期首残高に含まれているものとする
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Account {
+    name: String,
+    kind: AccountKind,
+    #[serde(default)]
+    opening_balance: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    opening_date: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    billing: Option<CardBilling>,
+}
+
+// クレジットカードの締め日と支払日。締め日までの利用分をpayment_month_offsetか月後の支払日に払う。
+// その月にない日(31日など)は月末とする
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct CardBilling {
+    closing_day: u32,
+    payment_day: u32,
+    #[serde(default = "get_default_payment_month_offset")]
+    payment_month_offset: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    payment_account: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountKind {
+    Cash,
+    Bank,
+    Card,
+    Prepaid,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct AccountBalance {
+    pub name: String,
+    pub kind: AccountKind,
+    pub balance: i64,
+}
+
+// 入出金明細の1行。balanceはその項目までの残高
+#[derive(Serialize, Debug, PartialEq)]
+pub struct StatementLine {
+    pub date: NaiveDate,
+    pub name: String,
+    pub amount: i64,
+    pub balance: i64,
+}
+
+impl AccountKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "cash" => Some(AccountKind::Cash),
+            "bank" => Some(AccountKind::Bank),
+            "card" => Some(AccountKind::Card),
+            "prepaid" => Some(AccountKind::Prepaid),
+            _ => None,
+        }
+    }
+
+    pub fn get_label(&self) -> &str {
+        match self {
+            AccountKind::Cash => "現金",
+            AccountKind::Bank => "銀行口座",
+            AccountKind::Card => "クレジットカード",
+            AccountKind::Prepaid => "電子マネー",
+        }
+    }
+}
+
+impl CardBilling {
+    pub fn new(closing_day: u32, payment_day: u32, payment_account: Option<String>) -> Self {
+        for day in [closing_day, payment_day] {
+            if !(1..=31).contains(&day) {
+                panic!("締め日・支払日は1から31で指定してください: {}", day);
+            }
+        }
+        CardBilling {
+            closing_day,
+            payment_day,
+            payment_month_offset: get_default_payment_month_offset(),
+            payment_account,
+        }
+    }
+
+    pub fn get_payment_account(&self) -> Option<&str> {
+        self.payment_account.as_deref()
+    }
+
+    // monthに支払う請求に含まれうる最も古い利用月の初日
+    pub fn get_first_usage_month(&self, month: NaiveDate) -> NaiveDate {
+        month.with_day(1).unwrap() - Months::new(self.payment_month_offset + 1)
+    }
+
+    // 利用日を含む請求期間の締め日
+    pub fn get_closing_date(&self, date: NaiveDate) -> NaiveDate {
+        let closing_date = get_day_in_month(date, self.closing_day);
+        if date <= closing_date {
+            closing_date
+        } else {
+            get_day_in_month(date.with_day(1).unwrap() + Months::new(1), self.closing_day)
+        }
+    }
+
+    pub fn get_payment_date(&self, closing_date: NaiveDate) -> NaiveDate {
+        get_day_in_month(
+            closing_date.with_day(1).unwrap() + Months::new(self.payment_month_offset),
+            self.payment_day,
+        )
+    }
+
+    pub fn get_label(&self) -> String {
+        let closing = format_day(self.closing_day);
+        let payment = format_day(self.payment_day);
+        match self.payment_month_offset {
+            0 => format!("{}締め当月{}払い", closing, payment),
+            1 => format!("{}締め翌月{}払い", closing, payment),
+            2 => format!("{}締め翌々月{}払い", closing, payment),
+            offset => format!("{}締め{}か月後{}払い", closing, offset, payment),
+        }
+    }
+}
+
+impl Account {
+    pub fn new(
+        name: String,
+        kind: AccountKind,
+        opening_balance: i64,
+        opening_date: Option<NaiveDate>,
+    ) -> Self {
+        Account {
+            name,
+            kind,
+            opening_balance,
+            opening_date,
+            billing: None,
+        }
+    }
+
+    pub fn with_billing(mut self, billing: Option<CardBilling>) -> Self {
+        self.billing = billing;
+        self
+    }
+
+    pub fn get_billing(&self) -> Option<&CardBilling> {
+        self.billing.as_ref()
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_opening_date(&self) -> Option<NaiveDate> {
+        self.opening_date
+    }
+
+    pub fn get_kind(&self) -> AccountKind {
+        self.kind
+    }
+
+    // 口座が未設定でも、支払方法が口座名と同じ項目はその口座の入出金とみなす
+    pub fn contains(&self, item: &models::Item) -> bool {
+        let name = item.get_account().or(item.get_payment_method());
+        name == Some(self.name.as_str()) && self.is_open(item.get_date())
+    }
+
+    pub fn get_balance(&self, data: &[models::Item], transfers: &[Transfer]) -> i64 {
+        self.opening_balance
+            + self
+                .get_entries(data, transfers)
+                .iter()
+                .map(|(_, _, amount)| amount)
+                .sum::<i64>()
+    }
+
+    // fromからtoまで(両端を含む)の明細と、fromの前日時点の残高
+    pub fn get_statement(
+        &self,
+        data: &[models::Item],
+        transfers: &[Transfer],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> (i64, Vec<StatementLine>) {
+        let entries = self.get_entries(data, transfers);
+        let carried = self.opening_balance
+            + entries
+                .iter()
+                .filter(|(date, _, _)| *date < from)
+                .map(|(_, _, amount)| amount)
+                .sum::<i64>();
+        let mut balance = carried;
+        let lines = entries
+            .into_iter()
+            .filter(|(date, _, _)| from <= *date && *date <= to)
+            .map(|(date, name, amount)| {
+                balance += amount;
+                StatementLine {
+                    date,
+                    name,
+                    amount,
+                    balance,
+                }
+            })
+            .collect();
+        (carried, lines)
+    }
+
+    fn is_open(&self, date: NaiveDate) -> bool {
+        self.opening_date
+            .is_none_or(|opening_date| date >= opening_date)
+    }
+
+    // 口座の入出金を日付順に並べた(日付, 内容, 増減)。同じ日は項目、振替の順
+    fn get_entries(
+        &self,
+        data: &[models::Item],
+        transfers: &[Transfer],
+    ) -> Vec<(NaiveDate, String, i64)> {
+        let mut items: Vec<&models::Item> =
+            data.iter().filter(|item| self.contains(item)).collect();
+        items.sort_by_key(|item| (item.get_date(), item.get_id()));
+        let mut entries: Vec<(NaiveDate, String, i64)> = items
+            .into_iter()
+            .map(|item| {
+                (
+                    item.get_date(),
+                    item.get_name().to_string(),
+                    item.get_price_for_summary() as i64,
+                )
+            })
+            .chain(
+                transfers
+                    .iter()
+                    .filter(|transfer| self.is_open(transfer.get_date()))
+                    .filter(|transfer| transfer.get_amount_for(&self.name) != 0)
+                    .map(|transfer| {
+                        (
+                            transfer.get_date(),
+                            transfer.get_label(),
+                            transfer.get_amount_for(&self.name),
+                        )
+                    }),
+            )
+            .collect();
+        entries.sort_by_key(|(date, _, _)| *date);
+        entries
+    }
+}
+
+pub fn run_list(config: &Config, output_format: OutputFormat) {
+    let accounts = read_accounts(&config.ledger_path);
+    let data = services::io::read_data_between(config, get_first_opening_date(&accounts), None);
+    let transfers = services::transfer::read_transfers(&config.ledger_path);
+    let balances: Vec<AccountBalance> = accounts
+        .iter()
+        .map(|account| AccountBalance {
+            name: account.name.clone(),
+            kind: account.kind,
+            balance: account.get_balance(&data, &transfers),
+        })
+        .collect();
+    if output_format == OutputFormat::Json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&balances).expect("JSONに変換できませんでした")
+        );
+        return;
+    }
+    if balances.is_empty() {
+        println!("口座が登録されていません");
+        return;
+    }
+    println!("口座の残高");
+    for balance in &balances {
+        println!(
+            "{}({}): {}",
+            balance.name,
+            balance.kind.get_label(),
+            config.currency.format(balance.balance)
+        );
+    }
+    let unlinked = data
+        .iter()
+        .filter(|item| !accounts.iter().any(|account| account.contains(item)))
+        .count();
+    if unlinked > 0 {
+        println!("口座が設定されていない項目: {}件", unlinked);
+    }
+}
+
+pub fn run_add(
+    config: &Config,
+    name: &str,
+    kind: &str,
+    opening_balance: Option<&str>,
+    opening_date: Option<&str>,
+    billing: Option<CardBilling>,
+) {
+    let mut accounts = read_accounts(&config.ledger_path);
+    if accounts.iter().any(|account| account.name == name) {
+        panic!("{}は登録済みです", name);
+    }
+    let kind =
+        AccountKind::from_name(kind).unwrap_or_else(|| panic!("--kindの値が不正です: {}", kind));
+    if let Some(billing) = &billing {
+        if kind != AccountKind::Card {
+            panic!("締め日・支払日はクレジットカード(--kind card)にのみ指定できます");
+        }
+        if let Some(payment_account) = billing.get_payment_account() {
+            find_or_panic(&config.ledger_path, payment_account);
+        }
+    }
+    let opening_balance: i64 = opening_balance
+        .map_or(Ok(0), |balance| balance.parse())
+        .expect("残高は数値で入力してください");
+    let opening_date = opening_date
+        .map(|date| NaiveDate::from_str(date).expect("日付はyyyy-mm-ddの形式で入力してください"));
+    let billing_label = billing
+        .as_ref()
+        .map_or(String::new(), |billing| format!(" {}", billing.get_label()));
+    accounts.push(
+        Account::new(name.to_string(), kind, opening_balance, opening_date).with_billing(billing),
+    );
+    write_accounts(&accounts, config, &format!("Add account: {}", name));
+    println!(
+        "{}({})を登録しました(期首残高: {}){}",
+        name,
+        kind.get_label(),
+        config.currency.format(opening_balance),
+        billing_label
+    );
+}
+
+// 締め日と支払日の両方が指定された場合だけ請求の設定を作る
+pub fn parse_billing(
+    closing_day: Option<&str>,
+    payment_day: Option<&str>,
+    payment_account: Option<&str>,
+) -> Option<CardBilling> {
+    match (closing_day, payment_day) {
+        (Some(closing_day), Some(payment_day)) => Some(CardBilling::new(
+            closing_day.parse().expect("締め日は数値で入力してください"),
+            payment_day.parse().expect("支払日は数値で入力してください"),
+            payment_account.map(|account| account.to_string()),
+        )),
+        (None, None) => None,
+        _ => panic!("--closing-dayと--payment-dayは両方指定してください"),
+    }
+}
+
+pub fn run_statement(config: &Config, name: &str, month: NaiveDate) {
+    let account = find_or_panic(&config.ledger_path, name);
+    let last_day = month + Months::new(1) - Days::new(1);
+    let data = services::io::read_data_between(config, account.opening_date, Some(last_day));
+    let transfers = services::transfer::read_transfers(&config.ledger_path);
+    let (carried, lines) = account.get_statement(&data, &transfers, month, last_day);
+    println!("{} {}年{}月の明細", name, month.year(), month.month());
+    println!("前月繰越: {}", config.currency.format(carried));
+    for line in &lines {
+        println!(
+            "{} {} {} 残高{}",
+            line.date,
+            line.name,
+            services::summarize::format_price(line.amount, &config.currency),
+            config.currency.format(line.balance)
+        );
+    }
+    let balance = lines.last().map_or(carried, |line| line.balance);
+    println!("月末残高: {}", config.currency.format(balance));
+}
+
+pub fn read_accounts(file_path: &str) -> Vec<Account> {
+    services::io::read_json_or_default(&services::io::get_sibling_path(file_path, ACCOUNT_FILE))
+}
+
+// 残高の計算に必要な最も古い日付。期首日のない口座があれば全期間が必要なのでNone
+pub fn get_first_opening_date(accounts: &[Account]) -> Option<NaiveDate> {
+    accounts
+        .iter()
+        .map(|account| account.opening_date)
+        .collect::<Option<Vec<NaiveDate>>>()?
+        .into_iter()
+        .min()
+}
+
+pub fn write_accounts(accounts: &[Account], config: &Config, message: &str) {
+    services::io::write_sidecar(&accounts, ACCOUNT_FILE, config, message);
+}
+
+fn get_default_payment_month_offset() -> u32 {
+    1
+}
+
+fn get_day_in_month(date: NaiveDate, day: u32) -> NaiveDate {
+    let first_day = date.with_day(1).unwrap();
+    let last_day = first_day + Months::new(1) - Days::new(1);
+    first_day.with_day(day.min(last_day.day())).unwrap()
+}
+
+fn format_day(day: u32) -> String {
+    if day >= 31 {
+        "末日".to_string()
+    } else {
+        format!("{}日", day)
+    }
+}
+
+pub fn find_or_panic(file_path: &str, name: &str) -> Account {
+    read_accounts(file_path)
+        .into_iter()
+        .find(|account| account.name == name)
+        .unwrap_or_else(|| panic!("口座が存在しません: {}", name))
+}
+
+#[cfg(test)]
+mod account_test {
+    use super::*;
+    use std::fs;
+
+    fn get_date(date: &str) -> NaiveDate {
+        NaiveDate::from_str(date).unwrap()
+    }
+
+    fn get_test_data() -> Vec<models::Item> {
+        let salary = models::Category::Income(models::IncomeCategory::Salary);
+        let food = models::Category::Expense(models::ExpenseCategory::Food);
+        vec![
+            models::Item::new("給料".to_string(), salary, 300000, get_date("2025-08-25"))
+                .with_id(1)
+                .with_account(Some("普通預金".to_string())),
+            models::Item::new("スーパー".to_string(), food, 3000, get_date("2025-08-10"))
+                .with_id(2)
+                .with_payment_method(Some("現金".to_string())),
+            models::Item::new("外食".to_string(), food, 5000, get_date("2025-07-20"))
+                .with_id(3)
+                .with_account(Some("現金".to_string())),
+            models::Item::new("期首前".to_string(), food, 1000, get_date("2025-06-30"))
+                .with_id(4)
+                .with_account(Some("現金".to_string())),
+            models::Item::new("未設定".to_string(), food, 700, get_date("2025-08-01")).with_id(5),
+        ]
+    }
+
+    fn get_test_transfers() -> Vec<Transfer> {
+        vec![Transfer::new(
+            1,
+            get_date("2025-08-05"),
+            "普通預金".to_string(),
+            "現金".to_string(),
+            30000,
+        )]
+    }
+
+    fn get_cash() -> Account {
+        Account::new(
+            "現金".to_string(),
+            AccountKind::Cash,
+            20000,
+            Some(get_date("2025-07-01")),
+        )
+    }
+
+    #[test]
+    fn test_get_balance() {
+        let data = get_test_data();
+        assert_eq!(get_cash().get_balance(&data, &[]), 12000);
+        let bank = Account::new("普通預金".to_string(), AccountKind::Bank, 0, None);
+        assert_eq!(bank.get_balance(&data, &[]), 300000);
+
+        // 振替は両方の口座の残高を動かすが、合計は変わらない
+        let transfers = get_test_transfers();
+        assert_eq!(get_cash().get_balance(&data, &transfers), 42000);
+        assert_eq!(bank.get_balance(&data, &transfers), 270000);
+    }
+
+    #[test]
+    fn test_get_statement() {
+        let (carried, lines) = get_cash().get_statement(
+            &get_test_data(),
+            &get_test_transfers(),
+            get_date("2025-08-01"),
+            get_date("2025-08-31"),
+        );
+        assert_eq!(carried, 15000);
+        assert_eq!(
+            lines,
+            vec![
+                StatementLine {
+                    date: get_date("2025-08-05"),
+                    name: "振替 普通預金→現金".to_string(),
+                    amount: 30000,
+                    balance: 45000,
+                },
+                StatementLine {
+                    date: get_date("2025-08-10"),
+                    name: "スーパー".to_string(),
+                    amount: -3000,
+                    balance: 42000,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_card_billing() {
+        let billing = CardBilling::new(15, 10, None);
+        assert_eq!(billing.get_label(), "15日締め翌月10日払い");
+        assert_eq!(
+            billing.get_closing_date(get_date("2025-08-15")),
+            get_date("2025-08-15")
+        );
+        assert_eq!(
+            billing.get_closing_date(get_date("2025-08-16")),
+            get_date("2025-09-15")
+        );
+        assert_eq!(
+            billing.get_payment_date(get_date("2025-09-15")),
+            get_date("2025-10-10")
+        );
+        // 10月の支払いには8月16日以降の利用が含まれる
+        assert_eq!(
+            billing.get_first_usage_month(get_date("2025-10-10")),
+            get_date("2025-08-01")
+        );
+
+        // 月末締め・翌月末払い
+        let billing = CardBilling::new(31, 31, None);
+        assert_eq!(billing.get_label(), "末日締め翌月末日払い");
+        assert_eq!(
+            billing.get_closing_date(get_date("2025-02-10")),
+            get_date("2025-02-28")
+        );
+        assert_eq!(
+            billing.get_payment_date(get_date("2025-01-31")),
+            get_date("2025-02-28")
+        );
+    }
+
+    #[test]
+    fn test_get_first_opening_date() {
+        let bank = Account::new(
+            "普通預金".to_string(),
+            AccountKind::Bank,
+            0,
+            Some(get_date("2025-04-01")),
+        );
+        assert_eq!(
+            get_first_opening_date(&[get_cash(), bank]),
+            Some(get_date("2025-04-01"))
+        );
+
+        let card = Account::new("カード".to_string(), AccountKind::Card, 0, None);
+        assert_eq!(get_first_opening_date(&[get_cash(), card]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "締め日・支払日は1から31で指定してください: 0")]
+    fn test_card_billing_invalid() {
+        CardBilling::new(0, 10, None);
+    }
+
+    #[test]
+    fn test_read_write_accounts() {
+        let test_dir = std::env::temp_dir().join("kakeibo_account_test");
+        fs::create_dir_all(&test_dir).unwrap();
+        let ledger_path = test_dir.join("data.json");
+        let mut config = Config {
+            ledger_path: ledger_path.to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+        config.backup.enabled = false;
+        let ledger_path = config.ledger_path.as_str();
+
+        write_accounts(&[get_cash()], &config, "Add account");
+        assert_eq!(read_accounts(ledger_path), vec![get_cash()]);
+        assert_eq!(find_or_panic(ledger_path, "現金"), get_cash());
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+}
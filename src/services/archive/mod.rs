@@ -0,0 +1,328 @@
+use crate::config::Config;
+use crate::{models, services};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+pub const INDEX_FILE: &str = "archives.json";
+
+// アーカイブした年の集計。残高は最初のアーカイブからその年までの累計を繰り越す
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ArchiveSummary {
+    year: i32,
+    file: String,
+    item_count: usize,
+    income: i32,
+    expense: i32,
+    carried_balance: i32,
+    #[serde(default)]
+    max_id: u32,
+}
+
+impl ArchiveSummary {
+    pub fn get_year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn get_file(&self) -> &str {
+        &self.file
+    }
+
+    pub fn get_carried_balance(&self) -> i32 {
+        self.carried_balance
+    }
+}
+
+pub fn run(config: &Config, year: &str) {
+    let year: i32 = year.parse().expect("年は数値で入力してください");
+    println!("{}のデータをアーカイブします", config.get_year_label(year));
+    let current_year = config.get_fiscal_year(Local::now().date_naive());
+    let summary = archive_year(config, year, current_year);
+    println!(
+        "{}件を{}に移動しました(収入: {}, 支出: {}, 繰越残高: {})",
+        summary.item_count,
+        summary.file,
+        config.currency.format(summary.income as i64),
+        config.currency.format(summary.expense as i64),
+        config.currency.format(summary.carried_balance as i64)
+    );
+}
+
+pub fn run_list(config: &Config) {
+    let index = read_index(&config.ledger_path);
+    if index.is_empty() {
+        println!("アーカイブが存在しません");
+        return;
+    }
+    println!("アーカイブ一覧");
+    for summary in index {
+        println!(
+            "{}: {}件 収入{} 支出{} 繰越残高{} ({})",
+            config.get_year_label(summary.year),
+            summary.item_count,
+            config.currency.format(summary.income as i64),
+            config.currency.format(summary.expense as i64),
+            config.currency.format(summary.carried_balance as i64),
+            summary.file
+        );
+    }
+}
+
+// 締められた年度(今年度より前)の項目をアーカイブファイルへ移す。年はfiscal_year_startに従う年度
+pub fn archive_year(config: &Config, year: i32, current_year: i32) -> ArchiveSummary {
+    if year >= current_year {
+        panic!("締められていない年はアーカイブできません");
+    }
+    let file_path = &config.ledger_path;
+    let (archived, active): (Vec<models::Item>, Vec<models::Item>) =
+        services::io::read_data(file_path)
+            .into_iter()
+            .partition(|item| config.get_fiscal_year(item.get_date()) == year);
+    if archived.is_empty() {
+        panic!("アーカイブ対象のデータが存在しません");
+    }
+
+    let file_name = get_archive_file_name(year);
+    let archive_path = services::io::get_sibling_path(file_path, &file_name);
+    let mut archive_data = if archive_path.exists() {
+        services::io::read_data(archive_path.to_str().unwrap())
+    } else {
+        Vec::new()
+    };
+    archive_data.extend(archived);
+    let mut index = read_index(file_path);
+    index.retain(|summary| summary.year != year);
+    index.push(summarize_archive(year, file_name, &archive_data));
+    update_carried_balance(&mut index);
+
+    // アーカイブ・家計簿・一覧をまとめて書き込み、1回のコミットにする
+    let index_path = services::io::get_sibling_path(file_path, INDEX_FILE);
+    services::io::write_items(&archive_data, &archive_path);
+    services::io::write_items(&active, std::path::Path::new(file_path));
+    services::io::write_json(&index, &index_path);
+    if config.backup.enabled {
+        for path in [file_path.as_str(), index_path.to_str().unwrap()] {
+            services::backup::create_snapshot(path, &config.backup);
+            services::backup::prune_snapshots(path, &config.backup);
+        }
+    }
+    if config.git.enabled {
+        services::git::commit(file_path, &format!("Archive: {}", year));
+    }
+    let moved: Vec<&models::Item> = archive_data.iter().collect();
+    services::reconcile::warn_broken_assertions(config, &moved);
+
+    index
+        .into_iter()
+        .find(|summary| summary.year == year)
+        .unwrap()
+}
+
+// アーカイブ済みの項目とIDが重複しないよう、次に使えるIDを返す
+pub fn get_next_id(file_path: &str) -> u32 {
+    read_index(file_path)
+        .iter()
+        .map(|summary| summary.max_id)
+        .max()
+        .unwrap_or(0)
+        + 1
+}
+
+pub fn read_index(file_path: &str) -> Vec<ArchiveSummary> {
+    services::io::read_json_or_default(&services::io::get_sibling_path(file_path, INDEX_FILE))
+}
+
+// 条件に合う年のアーカイブを年の古い順に読み込む
+pub fn read_archived_data<F: Fn(i32) -> bool>(file_path: &str, filter: F) -> Vec<models::Item> {
+    read_index(file_path)
+        .iter()
+        .filter(|summary| filter(summary.year))
+        .flat_map(|summary| {
+            let archive_path = services::io::get_sibling_path(file_path, &summary.file);
+            services::io::read_data(archive_path.to_str().unwrap())
+        })
+        .collect()
+}
+
+fn get_archive_file_name(year: i32) -> String {
+    format!("{}.json", year)
+}
+
+fn summarize_archive(year: i32, file: String, data: &[models::Item]) -> ArchiveSummary {
+    let income = data
+        .iter()
+        .map(|item| item.get_price_for_summary())
+        .filter(|price| *price > 0)
+        .sum();
+    let expense = data
+        .iter()
+        .map(|item| item.get_price_for_summary())
+        .filter(|price| *price < 0)
+        .sum::<i32>()
+        .abs();
+    ArchiveSummary {
+        year,
+        file,
+        item_count: data.len(),
+        income,
+        expense,
+        carried_balance: 0,
+        max_id: data.iter().map(|item| item.get_id()).max().unwrap_or(0),
+    }
+}
+
+fn update_carried_balance(index: &mut [ArchiveSummary]) {
+    index.sort_by_key(|summary| summary.year);
+    let mut balance = 0;
+    for summary in index.iter_mut() {
+        balance += summary.income - summary.expense;
+        summary.carried_balance = balance;
+    }
+}
+
+#[cfg(test)]
+mod archive_test {
+    use super::*;
+    use chrono::NaiveDate;
+    use std::fs;
+
+    fn get_test_item(
+        name: &str,
+        category: models::Category,
+        price: u32,
+        year: i32,
+    ) -> models::Item {
+        models::Item::new(
+            name.to_string(),
+            category,
+            price,
+            NaiveDate::from_ymd_opt(year, 3, 1).unwrap(),
+        )
+    }
+
+    fn get_test_data() -> Vec<models::Item> {
+        let salary = models::Category::Income(models::IncomeCategory::Salary);
+        let food = models::Category::Expense(models::ExpenseCategory::Food);
+        vec![
+            get_test_item("給料", salary, 300000, 2022).with_id(1),
+            get_test_item("外食", food, 5000, 2022).with_id(2),
+            get_test_item("給料", salary, 310000, 2023).with_id(3),
+            get_test_item("外食", food, 8000, 2023).with_id(4),
+            get_test_item("ランチ", food, 800, 2025).with_id(5),
+        ]
+    }
+
+    fn get_test_config(dir_name: &str) -> Config {
+        let test_dir = std::env::temp_dir().join(dir_name);
+        let _ = fs::remove_dir_all(&test_dir);
+        fs::create_dir_all(&test_dir).unwrap();
+        let mut config = Config {
+            ledger_path: test_dir.join("data.json").to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+        config.backup.enabled = false;
+        services::io::write_to_json(&get_test_data(), &config.ledger_path, &config);
+        config
+    }
+
+    #[test]
+    fn test_archive_year() {
+        let config = get_test_config("kakeibo_archive_test");
+
+        let summary = archive_year(&config, 2022, 2025);
+        assert_eq!(summary.item_count, 2);
+        assert_eq!(summary.income, 300000);
+        assert_eq!(summary.expense, 5000);
+        assert_eq!(summary.carried_balance, 295000);
+        assert_eq!(services::io::read_data(&config.ledger_path).len(), 3);
+
+        let summary = archive_year(&config, 2023, 2025);
+        assert_eq!(summary.carried_balance, 295000 + 302000);
+        assert_eq!(services::io::read_data(&config.ledger_path).len(), 1);
+
+        let index = read_index(&config.ledger_path);
+        assert_eq!(index.len(), 2);
+        assert_eq!(index[1].max_id, 4);
+        assert_eq!(get_next_id(&config.ledger_path), 5);
+        assert_eq!(index[0].file, "2022.json");
+
+        let all = services::io::read_data_with_archives_or_panic(&config.ledger_path);
+        assert_eq!(all.len(), 5);
+        let recent = services::io::read_data_for_years(&config.ledger_path, 2023, 2025);
+        assert_eq!(recent.len(), 3);
+        let date = NaiveDate::from_ymd_opt(2022, 12, 31).unwrap();
+        let until = services::io::read_data_between(&config, None, Some(date));
+        assert_eq!(until.len(), 3);
+
+        fs::remove_dir_all(std::path::Path::new(&config.ledger_path).parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_archive_fiscal_year() {
+        let mut config = get_test_config("kakeibo_archive_fiscal_year_test");
+        config.fiscal_year_start = 4;
+
+        // 2023年3月の項目は2022年度に含まれる
+        let summary = archive_year(&config, 2022, 2025);
+        assert_eq!(summary.item_count, 2);
+        assert_eq!(summary.income, 310000);
+        let active = services::io::read_data(&config.ledger_path);
+        assert_eq!(active.len(), 3);
+        assert!(active.iter().all(|item| item.get_year() != 2023));
+
+        fs::remove_dir_all(std::path::Path::new(&config.ledger_path).parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_archive_year_commit() {
+        let mut config = get_test_config("kakeibo_archive_commit_test");
+        config.git.enabled = true;
+        let test_dir = std::path::Path::new(&config.ledger_path).parent().unwrap();
+
+        // アーカイブ・家計簿・一覧の書き込みを1回でコミットする
+        archive_year(&config, 2022, 2025);
+
+        let git = |args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .arg("-C")
+                .arg(test_dir)
+                .args(args)
+                .output()
+                .unwrap();
+            String::from_utf8(output.stdout).unwrap()
+        };
+        assert_eq!(git(&["log", "--format=%s"]), "Archive: 2022\n");
+        assert_eq!(git(&["status", "--porcelain", "--", "*.json"]), "");
+        assert_eq!(
+            git(&["ls-files"]),
+            ".gitignore\n2022.json\narchives.json\ndata.json\n"
+        );
+
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "締められていない年はアーカイブできません")]
+    fn test_archive_year_current_year() {
+        archive_year(&Config::default(), 2025, 2025);
+    }
+
+    #[test]
+    fn test_update_carried_balance() {
+        let mut index = vec![
+            summarize_archive(2023, "2023.json".to_string(), &[]),
+            ArchiveSummary {
+                year: 2022,
+                file: "2022.json".to_string(),
+                item_count: 1,
+                income: 1000,
+                expense: 300,
+                carried_balance: 0,
+                max_id: 0,
+            },
+        ];
+        update_carried_balance(&mut index);
+        assert_eq!(index[0].year, 2022);
+        assert_eq!(index[0].carried_balance, 700);
+        assert_eq!(index[1].carried_balance, 700);
+    }
+}
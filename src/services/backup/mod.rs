@@ -0,0 +1,400 @@
+use crate::config::{BackupConfig, Config};
+use crate::{models, services};
+use chrono::{Datelike, Local, NaiveDateTime};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S-%3f";
+
+// fileは復元先のファイル名(家計簿なら家計簿ファイル、付随ファイルならbudgets.jsonなど)
+#[derive(Debug, PartialEq)]
+pub struct Snapshot {
+    name: String,
+    file: String,
+    path: PathBuf,
+    created_at: NaiveDateTime,
+}
+
+impl Snapshot {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_file(&self) -> &str {
+        &self.file
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get_created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+}
+
+// 家計簿は件数、付随ファイル(口座・予算など)はファイル名を添えて表示する
+pub fn run_list(config: &Config) {
+    let snapshots = list_all_snapshots(&config.ledger_path, &config.backup);
+    if snapshots.is_empty() {
+        println!("バックアップが存在しません");
+        return;
+    }
+    let ledger_file = get_file_name(&config.ledger_path);
+    println!("バックアップ一覧");
+    for snapshot in snapshots.iter().rev() {
+        let detail = if snapshot.file == ledger_file {
+            let data = services::io::read_data(snapshot.path.to_str().unwrap());
+            format!("{}件", data.len())
+        } else {
+            snapshot.file.clone()
+        };
+        println!(
+            "{}  {}  {}",
+            snapshot.name,
+            snapshot.created_at.format("%Y-%m-%d %H:%M:%S"),
+            detail
+        );
+    }
+}
+
+pub fn run_restore(config: &Config, snapshot_name: &str) {
+    let snapshots = list_all_snapshots(&config.ledger_path, &config.backup);
+    let snapshot = snapshots
+        .iter()
+        .find(|snapshot| snapshot.name == snapshot_name)
+        .expect("指定されたバックアップが存在しません");
+    if snapshot.file != get_file_name(&config.ledger_path) {
+        restore_sidecar(config, snapshot);
+        return;
+    }
+
+    let restored = services::io::read_data(snapshot.path.to_str().unwrap());
+    let current = if Path::new(&config.ledger_path).exists() {
+        services::io::read_data(&config.ledger_path)
+    } else {
+        Vec::new()
+    };
+    let (added, removed) = services::io::diff_items(&current, &restored);
+    print_diff_summary(&added, &removed);
+    if added.is_empty() && removed.is_empty() {
+        println!("現在のデータと差分がないため復元を中止します");
+        return;
+    }
+
+    println!("復元しますか？(y/n)");
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .expect("入力に失敗しました");
+    if answer.trim() != "y" {
+        println!("復元を中止しました");
+        return;
+    }
+    services::io::write_to_json(&restored, &config.ledger_path, config);
+    println!("{}から復元しました", snapshot.name);
+}
+
+// 付随ファイルは項目の差分を出せないため、内容が異なることを確認してから丸ごと戻す
+fn restore_sidecar(config: &Config, snapshot: &Snapshot) {
+    let restored: serde_json::Value = services::io::read_json_or_default(&snapshot.path);
+    let current_path = services::io::get_sibling_path(&config.ledger_path, &snapshot.file);
+    let current: serde_json::Value = services::io::read_json_or_default(&current_path);
+    if current == restored {
+        println!("現在のデータと差分がないため復元を中止します");
+        return;
+    }
+
+    println!(
+        "{}を{}の内容に戻しますか？(y/n)",
+        snapshot.file, snapshot.name
+    );
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .expect("入力に失敗しました");
+    if answer.trim() != "y" {
+        println!("復元を中止しました");
+        return;
+    }
+    let message = format!("Restore: {} from {}", snapshot.file, snapshot.name);
+    services::io::write_sidecar(&restored, &snapshot.file, config, &message);
+    println!("{}から復元しました", snapshot.name);
+}
+
+pub fn create_snapshot(file_path: &str, backup: &BackupConfig) -> PathBuf {
+    let dir = backup.get_dir(file_path);
+    fs::create_dir_all(&dir).expect("バックアップディレクトリの作成に失敗しました");
+    let timestamp = Local::now().naive_local().format(TIMESTAMP_FORMAT);
+    let path = dir.join(format!("{}-{}.json", get_file_stem(file_path), timestamp));
+    fs::copy(file_path, &path).expect("バックアップの作成に失敗しました");
+    path
+}
+
+// 古い順に並べて返す
+pub fn list_snapshots(file_path: &str, backup: &BackupConfig) -> Vec<Snapshot> {
+    let file = get_file_name(file_path);
+    list_all_snapshots(file_path, backup)
+        .into_iter()
+        .filter(|snapshot| snapshot.file == file)
+        .collect()
+}
+
+// バックアップディレクトリにある家計簿と付随ファイルのスナップショットを古い順に返す
+pub fn list_all_snapshots(file_path: &str, backup: &BackupConfig) -> Vec<Snapshot> {
+    let dir = backup.get_dir(file_path);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut snapshots: Vec<Snapshot> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let (stem, created_at) = split_snapshot_name(&name)?;
+            Some(Snapshot {
+                file: format!("{}.json", stem),
+                name,
+                path: entry.path(),
+                created_at,
+            })
+        })
+        .collect();
+    snapshots.sort_by_key(|snapshot| (snapshot.created_at, snapshot.name.clone()));
+    snapshots
+}
+
+pub fn prune_snapshots(file_path: &str, backup: &BackupConfig) {
+    let snapshots = list_snapshots(file_path, backup);
+    let retained = select_retained(&snapshots, backup);
+    for snapshot in snapshots {
+        if !retained.contains(&snapshot.name) {
+            fs::remove_file(&snapshot.path).expect("バックアップの削除に失敗しました");
+        }
+    }
+}
+
+fn get_file_stem(file_path: &str) -> String {
+    Path::new(file_path)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .expect("ファイル名が不正です")
+        .to_string()
+}
+
+fn get_file_name(file_path: &str) -> String {
+    format!("{}.json", get_file_stem(file_path))
+}
+
+fn parse_snapshot_name(name: &str, stem: &str) -> Option<NaiveDateTime> {
+    let timestamp = name
+        .strip_prefix(stem)?
+        .strip_prefix('-')?
+        .strip_suffix(".json")?;
+    NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()
+}
+
+// 「{stem}-{タイムスタンプ}.json」を元のファイル名の語幹と作成日時に分ける
+fn split_snapshot_name(name: &str) -> Option<(String, NaiveDateTime)> {
+    let body = name.strip_suffix(".json")?;
+    let (stem, _) = body.rsplit_once('-')?;
+    let (stem, _) = stem.rsplit_once('-')?;
+    let (stem, _) = stem.rsplit_once('-')?;
+    let created_at = parse_snapshot_name(name, stem)?;
+    Some((stem.to_string(), created_at))
+}
+
+fn select_retained(snapshots: &[Snapshot], backup: &BackupConfig) -> BTreeSet<String> {
+    let newest_first: Vec<&Snapshot> = snapshots.iter().rev().collect();
+    let mut retained: BTreeSet<String> = newest_first
+        .iter()
+        .take(backup.keep_last)
+        .map(|snapshot| snapshot.name.clone())
+        .collect();
+    retained.extend(select_per_period(
+        &newest_first,
+        backup.keep_daily,
+        |date| (date.year(), date.ordinal()),
+    ));
+    retained.extend(select_per_period(
+        &newest_first,
+        backup.keep_weekly,
+        |date| (date.iso_week().year(), date.iso_week().week()),
+    ));
+    retained.extend(select_per_period(
+        &newest_first,
+        backup.keep_monthly,
+        |date| (date.year(), date.month()),
+    ));
+    retained
+}
+
+// 期間ごとに最新のスナップショットを1件ずつ、新しい期間からcount件分選ぶ
+fn select_per_period<F>(newest_first: &[&Snapshot], count: usize, period_of: F) -> Vec<String>
+where
+    F: Fn(NaiveDateTime) -> (i32, u32),
+{
+    let mut selected = Vec::new();
+    let mut last_period = None;
+    for snapshot in newest_first {
+        if selected.len() >= count {
+            break;
+        }
+        let period = period_of(snapshot.created_at);
+        if last_period != Some(period) {
+            selected.push(snapshot.name.clone());
+            last_period = Some(period);
+        }
+    }
+    selected
+}
+
+fn print_diff_summary(added: &[&models::Item], removed: &[&models::Item]) {
+    println!("復元すると以下の変更が行われます");
+    println!("  追加: {}件", added.len());
+    for item in added {
+        println!("    + {}", format_item(item));
+    }
+    println!("  削除: {}件", removed.len());
+    for item in removed {
+        println!("    - {}", format_item(item));
+    }
+}
+
+fn format_item(item: &models::Item) -> String {
+    format!(
+        "{} {}({}) {}",
+        item.get_date(),
+        item.get_name(),
+        item.get_item_category().get_label(),
+        item.get_price_for_summary()
+    )
+}
+
+#[cfg(test)]
+mod backup_test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn get_snapshot(created_at: &str) -> Snapshot {
+        let created_at = NaiveDateTime::parse_from_str(created_at, "%Y-%m-%d %H:%M").unwrap();
+        let name = format!("data-{}.json", created_at.format(TIMESTAMP_FORMAT));
+        Snapshot {
+            path: PathBuf::from(&name),
+            file: "data.json".to_string(),
+            name,
+            created_at,
+        }
+    }
+
+    fn get_backup_config(
+        keep_last: usize,
+        daily: usize,
+        weekly: usize,
+        monthly: usize,
+    ) -> BackupConfig {
+        BackupConfig {
+            enabled: true,
+            dir: None,
+            keep_last,
+            keep_daily: daily,
+            keep_weekly: weekly,
+            keep_monthly: monthly,
+        }
+    }
+
+    #[test]
+    fn test_parse_snapshot_name() {
+        let result = parse_snapshot_name("data-20250801-093000-250.json", "data");
+        let expected = NaiveDate::from_ymd_opt(2025, 8, 1)
+            .unwrap()
+            .and_hms_milli_opt(9, 30, 0, 250);
+        assert_eq!(result, expected);
+
+        assert_eq!(
+            parse_snapshot_name("other-20250801-093000-250.json", "data"),
+            None
+        );
+        assert_eq!(parse_snapshot_name("data-latest.json", "data"), None);
+    }
+
+    #[test]
+    fn test_split_snapshot_name() {
+        let expected = NaiveDate::from_ymd_opt(2025, 8, 1)
+            .unwrap()
+            .and_hms_milli_opt(9, 30, 0, 250)
+            .unwrap();
+        assert_eq!(
+            split_snapshot_name("budgets-20250801-093000-250.json"),
+            Some(("budgets".to_string(), expected))
+        );
+        assert_eq!(
+            split_snapshot_name("my-data-20250801-093000-250.json"),
+            Some(("my-data".to_string(), expected))
+        );
+        assert_eq!(split_snapshot_name("data-latest.json"), None);
+    }
+
+    #[test]
+    fn test_select_retained_keep_last() {
+        let snapshots = vec![
+            get_snapshot("2025-08-01 09:00"),
+            get_snapshot("2025-08-01 10:00"),
+            get_snapshot("2025-08-01 11:00"),
+        ];
+        let retained = select_retained(&snapshots, &get_backup_config(2, 0, 0, 0));
+        assert_eq!(retained.len(), 2);
+        assert!(!retained.contains(&snapshots[0].name));
+    }
+
+    #[test]
+    fn test_select_retained_per_period() {
+        let snapshots = vec![
+            get_snapshot("2025-06-30 09:00"),
+            get_snapshot("2025-07-31 09:00"),
+            get_snapshot("2025-08-01 09:00"),
+            get_snapshot("2025-08-01 18:00"),
+            get_snapshot("2025-08-02 09:00"),
+        ];
+        // 日次2件: 8/2と8/1 18:00
+        let retained = select_retained(&snapshots, &get_backup_config(0, 2, 0, 0));
+        let expected: BTreeSet<String> = [&snapshots[3].name, &snapshots[4].name]
+            .into_iter()
+            .cloned()
+            .collect();
+        assert_eq!(retained, expected);
+
+        // 月次3件: 8月・7月・6月の最新
+        let retained = select_retained(&snapshots, &get_backup_config(0, 0, 0, 3));
+        let expected: BTreeSet<String> =
+            [&snapshots[0].name, &snapshots[1].name, &snapshots[4].name]
+                .into_iter()
+                .cloned()
+                .collect();
+        assert_eq!(retained, expected);
+    }
+
+    #[test]
+    fn test_prune_snapshots() {
+        let test_dir = std::env::temp_dir().join("kakeibo_backup_prune_test");
+        let _ = fs::remove_dir_all(&test_dir);
+        fs::create_dir_all(&test_dir).unwrap();
+        let test_file = test_dir.join("data.json");
+        let test_file = test_file.to_str().unwrap();
+        fs::write(test_file, "[]").unwrap();
+
+        let backup = get_backup_config(2, 0, 0, 0);
+        for _ in 0..3 {
+            create_snapshot(test_file, &backup);
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert_eq!(list_snapshots(test_file, &backup).len(), 3);
+
+        prune_snapshots(test_file, &backup);
+        assert_eq!(list_snapshots(test_file, &backup).len(), 2);
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+}
@@ -0,0 +1,316 @@
+use crate::config::{Config, OutputFormat};
+use crate::models::{Category, ExpenseCategory};
+use crate::{models, services};
+use chrono::{Datelike, Local, Months, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+pub const BUDGET_FILE: &str = "budgets.json";
+
+// 支出カテゴリーごとの月の予算。{"Food": 50000, "Hobby": 20000}
+pub type Budgets = BTreeMap<ExpenseCategory, u32>;
+
+// 月の予算の消化状況。projectedは今のペースで使い続けた場合の月末の支出
+#[derive(Serialize, Debug, PartialEq)]
+pub struct BudgetStatus {
+    pub category: ExpenseCategory,
+    pub label: String,
+    pub budget: u32,
+    pub spent: u32,
+    pub remaining: i64,
+    pub percentage: f64,
+    pub projected: u32,
+}
+
+pub fn run(config: &Config, month: NaiveDate, output_format: OutputFormat) {
+    let statuses = get_month_statuses(config, month, Local::now().date_naive());
+    if output_format == OutputFormat::Json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&statuses).expect("JSONに変換できませんでした")
+        );
+        return;
+    }
+    if statuses.is_empty() {
+        println!("予算が設定されていません");
+        return;
+    }
+    println!("{}年{}月の予算", month.year(), month.month());
+    for status in statuses {
+        println!("{}", format_status(&status, config));
+    }
+}
+
+// monthを含む年度のアーカイブも読み込んで、その月の予算の状況を求める
+fn get_month_statuses(config: &Config, month: NaiveDate, today: NaiveDate) -> Vec<BudgetStatus> {
+    let budgets = read_budgets(&config.ledger_path);
+    let data = services::io::read_data_between(config, Some(month), Some(month));
+    get_statuses(&data, &budgets, month, today)
+}
+
+pub fn run_set(config: &Config, category: &str, amount: &str) {
+    let category = ExpenseCategory::from_name(category)
+        .unwrap_or_else(|| panic!("支出のカテゴリーが不正です: {}", category));
+    let amount: u32 = amount.parse().expect("予算は数値で入力してください");
+    let mut budgets = read_budgets(&config.ledger_path);
+    let message = if amount == 0 {
+        budgets.remove(&category);
+        println!("{}の予算を削除しました", category.get_label());
+        format!("Remove budget: {}", category.get_label())
+    } else {
+        budgets.insert(category, amount);
+        println!(
+            "{}の予算を{}に設定しました",
+            category.get_label(),
+            config.currency.format(amount as i64)
+        );
+        format!(
+            "Set budget: {} {}",
+            category.get_label(),
+            config.currency.format(amount as i64)
+        )
+    };
+    write_budgets(&budgets, config, &message);
+}
+
+pub fn read_budgets(file_path: &str) -> Budgets {
+    services::io::read_json_or_default(&services::io::get_sibling_path(file_path, BUDGET_FILE))
+}
+
+pub fn write_budgets(budgets: &Budgets, config: &Config, message: &str) {
+    services::io::write_sidecar(budgets, BUDGET_FILE, config, message);
+}
+
+// 月初の日付(month)の月について、予算を設定したカテゴリーの消化状況を返す
+pub fn get_statuses(
+    data: &[models::Item],
+    budgets: &Budgets,
+    month: NaiveDate,
+    today: NaiveDate,
+) -> Vec<BudgetStatus> {
+    let days = get_days_in_month(month);
+    // 過去の月は月末まで、これからの月は経過日数0として扱う
+    let elapsed_days = if today < month {
+        0
+    } else if today.year() == month.year() && today.month() == month.month() {
+        today.day()
+    } else {
+        days
+    };
+    budgets
+        .iter()
+        .map(|(category, budget)| {
+            let spent = get_spent(data, *category, month);
+            let projected = if elapsed_days == 0 {
+                spent
+            } else {
+                (spent as u64 * days as u64 / elapsed_days as u64) as u32
+            };
+            BudgetStatus {
+                category: *category,
+                label: category.get_label().to_string(),
+                budget: *budget,
+                spent,
+                remaining: *budget as i64 - spent as i64,
+                percentage: spent as f64 * 100.0 / *budget as f64,
+                projected,
+            }
+        })
+        .collect()
+}
+
+// 登録する項目でその月の予算を超える場合の警告
+pub fn get_over_budget_warning(
+    data: &[models::Item],
+    item: &models::Item,
+    config: &Config,
+) -> Option<String> {
+    let budgets = read_budgets(&config.ledger_path);
+    // 内訳のある項目は内訳のカテゴリーごとに判定し、最初に超えたものを返す
+    item.get_category_prices()
+        .into_iter()
+        .find_map(|(category, price)| {
+            let Category::Expense(category) = category else {
+                return None;
+            };
+            let budget = *budgets.get(&category)?;
+            let spent = get_spent(data, category, item.get_first_day()) + price;
+            (spent > budget).then(|| get_warning(item, category, spent, budget, config))
+        })
+}
+
+pub fn format_status(status: &BudgetStatus, config: &Config) -> String {
+    let remaining = if status.remaining < 0 {
+        format!("超過{}", config.currency.format(-status.remaining))
+    } else {
+        format!("残り{}", config.currency.format(status.remaining))
+    };
+    format!(
+        "{}: {} / {} ({:.1}%) {} 月末見込み{}",
+        status.label,
+        config.currency.format(status.spent as i64),
+        config.currency.format(status.budget as i64),
+        status.percentage,
+        remaining,
+        config.currency.format(status.projected as i64)
+    )
+}
+
+fn get_warning(
+    item: &models::Item,
+    category: ExpenseCategory,
+    spent: u32,
+    budget: u32,
+    config: &Config,
+) -> String {
+    format!(
+        "{}の予算を超えます: {}年{}月の支出{} / 予算{}(超過{})",
+        category.get_label(),
+        item.get_year(),
+        item.get_month(),
+        config.currency.format(spent as i64),
+        config.currency.format(budget as i64),
+        config.currency.format((spent - budget) as i64)
+    )
+}
+
+fn get_spent(data: &[models::Item], category: ExpenseCategory, month: NaiveDate) -> u32 {
+    data.iter()
+        .filter(|item| item.get_first_day() == month)
+        .flat_map(|item| item.get_category_prices())
+        .filter(|(item_category, _)| *item_category == Category::Expense(category))
+        .map(|(_, price)| price)
+        .sum()
+}
+
+fn get_days_in_month(month: NaiveDate) -> u32 {
+    ((month + Months::new(1)) - month).num_days() as u32
+}
+
+#[cfg(test)]
+mod budget_test {
+    use super::*;
+    use std::fs;
+
+    fn get_test_item(category: ExpenseCategory, price: u32, date: &str) -> models::Item {
+        models::Item::new(
+            "テスト".to_string(),
+            Category::Expense(category),
+            price,
+            NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+        )
+    }
+
+    fn get_test_data() -> Vec<models::Item> {
+        vec![
+            get_test_item(ExpenseCategory::Food, 20000, "2025-08-05"),
+            get_test_item(ExpenseCategory::Food, 10000, "2025-08-10"),
+            get_test_item(ExpenseCategory::Hobby, 25000, "2025-08-12"),
+            get_test_item(ExpenseCategory::Food, 40000, "2025-07-20"),
+        ]
+    }
+
+    fn get_test_budgets() -> Budgets {
+        BTreeMap::from([
+            (ExpenseCategory::Food, 50000),
+            (ExpenseCategory::Hobby, 20000),
+        ])
+    }
+
+    fn get_date(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_get_statuses() {
+        let statuses = get_statuses(
+            &get_test_data(),
+            &get_test_budgets(),
+            get_date("2025-08-01"),
+            get_date("2025-08-15"),
+        );
+        assert_eq!(
+            statuses[0],
+            BudgetStatus {
+                category: ExpenseCategory::Food,
+                label: "食費".to_string(),
+                budget: 50000,
+                spent: 30000,
+                remaining: 20000,
+                percentage: 60.0,
+                projected: 62000,
+            }
+        );
+        assert_eq!(statuses[1].remaining, -5000);
+        assert_eq!(
+            format_status(&statuses[1], &Config::default()),
+            "趣味: 25000円 / 20000円 (125.0%) 超過5000円 月末見込み51666円"
+        );
+    }
+
+    #[test]
+    fn test_get_statuses_past_month() {
+        let statuses = get_statuses(
+            &get_test_data(),
+            &get_test_budgets(),
+            get_date("2025-07-01"),
+            get_date("2025-08-15"),
+        );
+        assert_eq!(statuses[0].spent, 40000);
+        assert_eq!(statuses[0].projected, 40000);
+        assert_eq!(statuses[1].spent, 0);
+    }
+
+    #[test]
+    fn test_get_over_budget_warning() {
+        let test_dir = std::env::temp_dir().join("kakeibo_budget_test");
+        fs::create_dir_all(&test_dir).unwrap();
+        let ledger_path = test_dir.join("data.json");
+        let mut config = Config {
+            ledger_path: ledger_path.to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+        config.backup.enabled = false;
+        write_budgets(&get_test_budgets(), &config, "Set budget");
+        assert_eq!(read_budgets(&config.ledger_path), get_test_budgets());
+
+        let data = get_test_data();
+        let item = get_test_item(ExpenseCategory::Food, 20000, "2025-08-20");
+        assert_eq!(get_over_budget_warning(&data, &item, &config), None);
+        let item = get_test_item(ExpenseCategory::Food, 25000, "2025-08-20");
+        assert_eq!(
+            get_over_budget_warning(&data, &item, &config).unwrap(),
+            "食費の予算を超えます: 2025年8月の支出55000円 / 予算50000円(超過5000円)"
+        );
+        let item = get_test_item(ExpenseCategory::Other, 100000, "2025-08-20");
+        assert_eq!(get_over_budget_warning(&data, &item, &config), None);
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_get_month_statuses_fiscal_year() {
+        let test_dir = std::env::temp_dir().join("kakeibo_budget_fiscal_year_test");
+        let _ = fs::remove_dir_all(&test_dir);
+        fs::create_dir_all(&test_dir).unwrap();
+        let mut config = Config {
+            ledger_path: test_dir.join("data.json").to_str().unwrap().to_string(),
+            fiscal_year_start: 4,
+            ..Default::default()
+        };
+        config.backup.enabled = false;
+        let data = vec![
+            get_test_item(ExpenseCategory::Food, 12000, "2025-02-10"),
+            get_test_item(ExpenseCategory::Food, 3000, "2025-08-10"),
+        ];
+        services::io::write_to_json(&data, &config.ledger_path, &config);
+        write_budgets(&get_test_budgets(), &config, "Set budget");
+        // 2025年2月は2024年度のアーカイブに入る
+        services::archive::archive_year(&config, 2024, 2025);
+
+        let statuses = get_month_statuses(&config, get_date("2025-02-01"), get_date("2025-08-15"));
+        assert_eq!(statuses[0].spent, 12000);
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+}
@@ -0,0 +1,218 @@
+use crate::config::Config;
+use crate::services::account::Account;
+use crate::{models, services};
+use chrono::{Datelike, NaiveDate};
+
+// 1回の請求。amountは支払う金額(返金があれば差し引く)
+#[derive(Debug, PartialEq)]
+pub struct CardStatement {
+    pub card: String,
+    pub closing_date: NaiveDate,
+    pub payment_date: NaiveDate,
+    pub payment_account: Option<String>,
+    pub items: Vec<models::Item>,
+    pub amount: i64,
+}
+
+pub fn run_statement(config: &Config, name: &str, month: Option<NaiveDate>) {
+    let account = services::account::find_or_panic(&config.ledger_path, name);
+    let Some(billing) = account.get_billing() else {
+        panic!("{}に締め日・支払日が設定されていません", name);
+    };
+    let from = month.map(|month| billing.get_first_usage_month(month));
+    let data = services::io::read_data_between(config, from, None);
+    println!("{} {}", name, billing.get_label());
+    let statements: Vec<CardStatement> = get_statements(&account, &data)
+        .into_iter()
+        .filter(|statement| month.is_none_or(|month| is_same_month(statement.payment_date, month)))
+        .collect();
+    if statements.is_empty() {
+        println!("請求がありません");
+        return;
+    }
+    for statement in statements {
+        println!(
+            "{}締め {}支払い {}",
+            statement.closing_date,
+            statement.payment_date,
+            config.currency.format(statement.amount)
+        );
+        for item in &statement.items {
+            println!(
+                "  {} {} {}",
+                item.get_date(),
+                item.get_name(),
+                config
+                    .currency
+                    .format(-(item.get_price_for_summary() as i64))
+            );
+        }
+    }
+}
+
+// month以降に支払日が来る請求を支払日順に表示する
+pub fn run_cashflow(config: &Config, month: NaiveDate) {
+    let accounts = services::account::read_accounts(&config.ledger_path);
+    let from = accounts
+        .iter()
+        .filter_map(|account| account.get_billing())
+        .map(|billing| billing.get_first_usage_month(month))
+        .min();
+    let data = services::io::read_data_between(config, from, None);
+    let mut statements: Vec<CardStatement> = accounts
+        .iter()
+        .flat_map(|account| get_statements(account, &data))
+        .filter(|statement| statement.payment_date >= month)
+        .collect();
+    if statements.is_empty() {
+        println!(
+            "{}年{}月以降の支払いはありません",
+            month.year(),
+            month.month()
+        );
+        return;
+    }
+    statements.sort_by_key(|statement| statement.payment_date);
+    println!("カードの支払予定");
+    for statement in &statements {
+        let payment_account = statement
+            .payment_account
+            .as_ref()
+            .map_or(String::new(), |account| {
+                format!(" 引き落とし口座: {}", account)
+            });
+        println!(
+            "{} {} {} ({}締め){}",
+            statement.payment_date,
+            statement.card,
+            config.currency.format(statement.amount),
+            statement.closing_date,
+            payment_account
+        );
+    }
+}
+
+// 締め日ごとにまとめた請求。締め日・支払日が設定されていない口座は空
+pub fn get_statements(account: &Account, data: &[models::Item]) -> Vec<CardStatement> {
+    let Some(billing) = account.get_billing() else {
+        return Vec::new();
+    };
+    let mut items: Vec<&models::Item> = data.iter().filter(|item| account.contains(item)).collect();
+    items.sort_by_key(|item| (item.get_date(), item.get_id()));
+    let mut statements: Vec<CardStatement> = Vec::new();
+    for item in items {
+        let closing_date = billing.get_closing_date(item.get_date());
+        let statement = match statements.last_mut() {
+            Some(statement) if statement.closing_date == closing_date => statement,
+            _ => {
+                statements.push(CardStatement {
+                    card: account.get_name().to_string(),
+                    closing_date,
+                    payment_date: billing.get_payment_date(closing_date),
+                    payment_account: billing
+                        .get_payment_account()
+                        .map(|account| account.to_string()),
+                    items: Vec::new(),
+                    amount: 0,
+                });
+                statements.last_mut().unwrap()
+            }
+        };
+        statement.amount -= item.get_price_for_summary() as i64;
+        statement.items.push(item.clone());
+    }
+    statements
+}
+
+// カードで支払った項目の日付を支払日に置き換える(支払日基準の集計用)
+pub fn to_payment_basis(data: &[models::Item], accounts: &[Account]) -> Vec<models::Item> {
+    data.iter()
+        .map(|item| {
+            let payment_date = accounts
+                .iter()
+                .filter(|account| account.contains(item))
+                .find_map(|account| account.get_billing())
+                .map(|billing| billing.get_payment_date(billing.get_closing_date(item.get_date())));
+            match payment_date {
+                Some(payment_date) => item.clone().with_date(payment_date),
+                None => item.clone(),
+            }
+        })
+        .collect()
+}
+
+fn is_same_month(date: NaiveDate, month: NaiveDate) -> bool {
+    date.year() == month.year() && date.month() == month.month()
+}
+
+#[cfg(test)]
+mod card_test {
+    use super::*;
+    use crate::services::account::{AccountKind, CardBilling};
+    use std::str::FromStr;
+
+    fn get_date(date: &str) -> NaiveDate {
+        NaiveDate::from_str(date).unwrap()
+    }
+
+    fn get_test_item(
+        name: &str,
+        category: models::Category,
+        price: u32,
+        date: &str,
+    ) -> models::Item {
+        models::Item::new(name.to_string(), category, price, get_date(date))
+            .with_account(Some("楽天カード".to_string()))
+    }
+
+    fn get_test_data() -> Vec<models::Item> {
+        let food = models::Category::Expense(models::ExpenseCategory::Food);
+        let refund = models::Category::Income(models::IncomeCategory::Other);
+        vec![
+            get_test_item("スーパー", food, 3000, "2025-07-20"),
+            get_test_item("外食", food, 5000, "2025-08-15"),
+            get_test_item("返品", refund, 1000, "2025-08-01"),
+            get_test_item("書店", food, 2000, "2025-08-16"),
+            models::Item::new("現金払い".to_string(), food, 700, get_date("2025-08-16")),
+        ]
+    }
+
+    fn get_test_card() -> Account {
+        Account::new("楽天カード".to_string(), AccountKind::Card, 0, None)
+            .with_billing(Some(CardBilling::new(15, 10, Some("普通預金".to_string()))))
+    }
+
+    #[test]
+    fn test_get_statements() {
+        let statements = get_statements(&get_test_card(), &get_test_data());
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].closing_date, get_date("2025-08-15"));
+        assert_eq!(statements[0].payment_date, get_date("2025-09-10"));
+        assert_eq!(statements[0].payment_account, Some("普通預金".to_string()));
+        assert_eq!(statements[0].items.len(), 3);
+        assert_eq!(statements[0].amount, 7000);
+        assert_eq!(statements[1].payment_date, get_date("2025-10-10"));
+        assert_eq!(statements[1].amount, 2000);
+    }
+
+    #[test]
+    fn test_get_statements_without_billing() {
+        let account = Account::new("現金".to_string(), AccountKind::Cash, 0, None);
+        assert!(get_statements(&account, &get_test_data()).is_empty());
+    }
+
+    #[test]
+    fn test_to_payment_basis() {
+        let data = to_payment_basis(&get_test_data(), &[get_test_card()]);
+        assert_eq!(data[0].get_date(), get_date("2025-09-10"));
+        assert_eq!(data[3].get_date(), get_date("2025-10-10"));
+        assert_eq!(data[4].get_date(), get_date("2025-08-16"));
+
+        // 利用日基準では8月の支出が、支払日基準では9月・10月に移る
+        let usage = services::summarize::get_monthly_table(&get_test_data());
+        let payment = services::summarize::get_monthly_table(&data);
+        assert_eq!(usage[&get_date("2025-08-01")], -6700);
+        assert_eq!(payment[&get_date("2025-08-01")], -700);
+        assert_eq!(payment[&get_date("2025-09-01")], -7000);
+    }
+}
@@ -0,0 +1,607 @@
+use crate::config::Config;
+use crate::{models, services};
+use chrono::{Local, NaiveDate};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+
+const MIN_PLAUSIBLE_YEAR: i32 = 1900;
+const MAX_PLAUSIBLE_PRICE: u32 = 10_000_000;
+
+#[derive(Debug, PartialEq)]
+pub enum Issue {
+    SchemaViolation { index: usize, message: String },
+    UnknownCategory { index: usize, category: String },
+    ImplausibleDate { index: usize, date: NaiveDate },
+    ZeroPrice { index: usize },
+    AbsurdPrice { index: usize, price: u32 },
+    PlaceholderName { index: usize, name: String },
+    MissingId { index: usize },
+    DuplicateId { index: usize, id: u32 },
+    SplitMismatch { index: usize, sum: u32, price: u32 },
+    SplitKindMismatch { index: usize, category: String },
+    ZeroSplitPrice { index: usize },
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FixMode {
+    Report,
+    Interactive,
+    Auto,
+}
+
+impl Issue {
+    pub fn get_index(&self) -> usize {
+        match self {
+            Issue::SchemaViolation { index, .. }
+            | Issue::UnknownCategory { index, .. }
+            | Issue::ImplausibleDate { index, .. }
+            | Issue::ZeroPrice { index }
+            | Issue::AbsurdPrice { index, .. }
+            | Issue::PlaceholderName { index, .. }
+            | Issue::MissingId { index }
+            | Issue::DuplicateId { index, .. }
+            | Issue::SplitMismatch { index, .. }
+            | Issue::SplitKindMismatch { index, .. }
+            | Issue::ZeroSplitPrice { index } => *index,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Issue::SchemaViolation { message, .. } => format!("形式が不正です: {}", message),
+            Issue::UnknownCategory { category, .. } => {
+                format!("不明なカテゴリーです: {}", category)
+            }
+            Issue::ImplausibleDate { date, .. } => format!("日付が不自然です: {}", date),
+            Issue::ZeroPrice { .. } => "金額が0円です".to_string(),
+            Issue::AbsurdPrice { price, .. } => format!("金額が大きすぎます: {}", price),
+            Issue::PlaceholderName { name, .. } => format!("品目名が仮の値です: \"{}\"", name),
+            Issue::MissingId { .. } => "IDが未設定です".to_string(),
+            Issue::DuplicateId { id, .. } => format!("IDが重複しています: {}", id),
+            Issue::SplitMismatch { sum, price, .. } => {
+                format!("内訳の合計が金額と一致しません: 内訳{} 金額{}", sum, price)
+            }
+            Issue::SplitKindMismatch { category, .. } => {
+                format!("内訳のカテゴリーの収支が項目と異なります: {}", category)
+            }
+            Issue::ZeroSplitPrice { .. } => "内訳に0円の行があります".to_string(),
+        }
+    }
+}
+
+pub fn run(config: &Config, mode: FixMode) {
+    println!("家計簿データを検査します");
+    let mut values = read_values(&config.ledger_path);
+    let today = Local::now().date_naive();
+    // アーカイブ済みの項目とIDが重複しないよう、アーカイブのIDも検査・採番に使う
+    let archived_ids: HashSet<u32> =
+        services::archive::read_archived_data(&config.ledger_path, |_| true)
+            .iter()
+            .map(|item| item.get_id())
+            .collect();
+    let next_id = services::archive::get_next_id(&config.ledger_path);
+    let issues = find_issues(&values, &archived_ids, today);
+    // 項目を読み込めない場合は照合した残高の検査をしない
+    let broken = if to_items(values.clone()).is_some() {
+        services::reconcile::check(config)
+    } else {
+        Vec::new()
+    };
+    if issues.is_empty() && broken.is_empty() {
+        println!("問題は見つかりませんでした");
+        return;
+    }
+    for issue in &issues {
+        println!("[{}] {}", issue.get_index(), issue.describe());
+    }
+    for broken in &broken {
+        println!("[照合] {}", broken.describe(config));
+    }
+    println!("{}件の問題が見つかりました", issues.len() + broken.len());
+    // 照合した残高との不一致は自動では直さない
+    if issues.is_empty() {
+        return;
+    }
+
+    let fixed = match mode {
+        FixMode::Report => return,
+        FixMode::Auto => apply_auto_fixes(&mut values, &issues, next_id),
+        FixMode::Interactive => apply_interactive_fixes(&mut values, &issues, next_id),
+    };
+    if !fixed {
+        println!("家計簿データは変更していません");
+        return;
+    }
+    let Some(data) = to_items(values) else {
+        println!("形式が不正な項目が残っているため保存できません");
+        return;
+    };
+    services::io::write_to_json(&data, &config.ledger_path, config);
+    let remaining = find_issues(&read_values(&config.ledger_path), &archived_ids, today);
+    println!("修正後の問題: {}件", remaining.len());
+}
+
+pub fn find_issues(values: &[Value], archived_ids: &HashSet<u32>, today: NaiveDate) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for (index, value) in values.iter().enumerate() {
+        match serde_json::from_value::<models::Item>(value.clone()) {
+            Ok(item) => issues.extend(check_item(index, &item, today)),
+            Err(e) => issues.push(check_schema(index, value, e)),
+        }
+    }
+    issues.extend(check_ids(values, archived_ids));
+    issues.sort_by_key(|issue| issue.get_index());
+    issues
+}
+
+fn read_values(file_path: &str) -> Vec<Value> {
+    let content = fs::read_to_string(file_path).expect("ファイルがオープンできませんでした");
+    match serde_json::from_str(&content).expect("JSONとして読み込めません") {
+        Value::Array(values) => values,
+        _ => panic!("家計簿データは配列である必要があります"),
+    }
+}
+
+fn check_item(index: usize, item: &models::Item, today: NaiveDate) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let date = item.get_date();
+    let max_date = today + chrono::Months::new(12);
+    if date < NaiveDate::from_ymd_opt(MIN_PLAUSIBLE_YEAR, 1, 1).unwrap() || date > max_date {
+        issues.push(Issue::ImplausibleDate { index, date });
+    }
+    if item.get_price() == 0 {
+        issues.push(Issue::ZeroPrice { index });
+    } else if item.get_price() > MAX_PLAUSIBLE_PRICE {
+        issues.push(Issue::AbsurdPrice {
+            index,
+            price: item.get_price(),
+        });
+    }
+    if is_placeholder_name(item.get_name()) {
+        issues.push(Issue::PlaceholderName {
+            index,
+            name: item.get_name().to_string(),
+        });
+    }
+    // 内訳の収支が項目と異なると、カテゴリー別の集計と月の収支が食い違う
+    let is_income = |category: &models::Category| matches!(category, models::Category::Income(_));
+    for split in item.get_splits() {
+        if is_income(split.get_category()) != is_income(item.get_item_category()) {
+            issues.push(Issue::SplitKindMismatch {
+                index,
+                category: split.get_category().get_label().to_string(),
+            });
+        }
+    }
+    if item.get_splits().iter().any(|split| split.get_price() == 0) {
+        issues.push(Issue::ZeroSplitPrice { index });
+    }
+    let sum: u32 = item
+        .get_splits()
+        .iter()
+        .map(|split| split.get_price())
+        .sum();
+    if !item.get_splits().is_empty() && sum != item.get_price() {
+        issues.push(Issue::SplitMismatch {
+            index,
+            sum,
+            price: item.get_price(),
+        });
+    }
+    issues
+}
+
+// 空欄・数字のみ・半角1文字の品目名は入力途中の仮の値とみなす
+fn is_placeholder_name(name: &str) -> bool {
+    let name = name.trim();
+    name.is_empty()
+        || name.chars().all(|c| c.is_ascii_digit())
+        || (name.chars().count() == 1 && name.is_ascii())
+}
+
+fn check_schema(index: usize, value: &Value, error: serde_json::Error) -> Issue {
+    match get_unknown_category(value) {
+        Some(category) => Issue::UnknownCategory { index, category },
+        None => Issue::SchemaViolation {
+            index,
+            message: error.to_string(),
+        },
+    }
+}
+
+fn get_unknown_category(value: &Value) -> Option<String> {
+    let category = value.get("category")?;
+    if serde_json::from_value::<models::Category>(category.clone()).is_ok() {
+        return None;
+    }
+    match category {
+        Value::Object(map) if map.len() == 1 => {
+            let (kind, name) = map.iter().next().unwrap();
+            Some(format!("{}:{}", kind, name.as_str().unwrap_or("?")))
+        }
+        _ => None,
+    }
+}
+
+fn get_id(value: &Value) -> u32 {
+    value.get("id").and_then(|id| id.as_u64()).unwrap_or(0) as u32
+}
+
+fn check_ids(values: &[Value], archived_ids: &HashSet<u32>) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut seen = archived_ids.clone();
+    for (index, value) in values.iter().enumerate() {
+        let id = get_id(value);
+        if id == 0 {
+            issues.push(Issue::MissingId { index });
+        } else if !seen.insert(id) {
+            issues.push(Issue::DuplicateId { index, id });
+        }
+    }
+    issues
+}
+
+// 項目として読み込めないもの(形式不正・不明なカテゴリー)が残ると保存できない
+fn is_unreadable(issue: &Issue) -> bool {
+    matches!(
+        issue,
+        Issue::SchemaViolation { .. } | Issue::UnknownCategory { .. }
+    )
+}
+
+// IDの採番と不明なカテゴリーの「その他」への置き換えだけを自動で行う。
+// 自動で読み込めるようにできない項目があれば何も変更せずにfalseを返す
+fn apply_auto_fixes(values: &mut [Value], issues: &[Issue], next_id: u32) -> bool {
+    let unfixable: Vec<usize> = issues
+        .iter()
+        .filter(|issue| match issue {
+            Issue::SchemaViolation { .. } => true,
+            Issue::UnknownCategory { category, .. } => {
+                !matches!(category.split(':').next(), Some("Income" | "Expense"))
+            }
+            _ => false,
+        })
+        .map(|issue| issue.get_index())
+        .collect();
+    if !unfixable.is_empty() {
+        for index in unfixable {
+            println!("[{}] 形式が不正なため自動修正できません", index);
+        }
+        println!("--fixで修正または削除してください");
+        return false;
+    }
+    let mut id_indexes = Vec::new();
+    for issue in issues {
+        let value = &mut values[issue.get_index()];
+        match issue {
+            Issue::MissingId { index } | Issue::DuplicateId { index, .. } => {
+                id_indexes.push(*index);
+            }
+            Issue::UnknownCategory { category, .. } => match category.split(':').next() {
+                Some(kind @ ("Income" | "Expense")) => {
+                    value["category"] = serde_json::json!({ kind: "Other" });
+                }
+                _ => println!("[{}] 自動修正できません", issue.get_index()),
+            },
+            _ => println!("[{}] 自動修正できません", issue.get_index()),
+        }
+    }
+    assign_ids(values, &id_indexes, next_id);
+    true
+}
+
+// 中止した場合はfalseを返す
+fn apply_interactive_fixes(values: &mut Vec<Value>, issues: &[Issue], next_id: u32) -> bool {
+    let mut removed = HashSet::new();
+    // 読み込めない項目は保存の妨げになるため、他の修正の前に修正か削除を選んでもらう
+    for issue in issues.iter().filter(|issue| is_unreadable(issue)) {
+        let index = issue.get_index();
+        println!("[{}] {} {}", index, issue.describe(), values[index]);
+        let fixable = matches!(issue, Issue::UnknownCategory { .. });
+        let message = if fixable {
+            "0: 修正, 1: 削除, 2: 中止"
+        } else {
+            "1: 削除, 2: 中止"
+        };
+        match input_line(message).as_str() {
+            "0" if fixable => fix_value(&mut values[index], issue),
+            "1" => {
+                removed.insert(index);
+            }
+            _ => {
+                println!("修正を中止しました");
+                return false;
+            }
+        }
+    }
+    let mut id_indexes = Vec::new();
+    for issue in issues {
+        let index = issue.get_index();
+        if removed.contains(&index) || is_unreadable(issue) {
+            continue;
+        }
+        if matches!(issue, Issue::MissingId { .. } | Issue::DuplicateId { .. }) {
+            id_indexes.push(index);
+            continue;
+        }
+        println!("[{}] {} {}", index, issue.describe(), values[index]);
+        match input_line("0: 修正, 1: 削除, 2: スキップ").as_str() {
+            "0" => fix_value(&mut values[index], issue),
+            "1" => {
+                removed.insert(index);
+            }
+            _ => {}
+        }
+    }
+    id_indexes.retain(|index| !removed.contains(index));
+    if !id_indexes.is_empty() && input_line("IDを採番し直しますか？(y/n)") == "y" {
+        assign_ids(values, &id_indexes, next_id);
+    }
+    let mut index = 0;
+    values.retain(|_| {
+        index += 1;
+        !removed.contains(&(index - 1))
+    });
+    true
+}
+
+// next_idはアーカイブを含めた次のID。家計簿ファイルの最大のIDの次と大きい方から採番する
+fn assign_ids(values: &mut [Value], indexes: &[usize], next_id: u32) {
+    let next_id = (values.iter().map(get_id).max().unwrap_or(0) + 1).max(next_id);
+    for (id, index) in (next_id..).zip(indexes) {
+        values[*index]["id"] = Value::from(id);
+    }
+}
+
+fn fix_value(value: &mut Value, issue: &Issue) {
+    match issue {
+        Issue::ImplausibleDate { .. } => {
+            let date = input_line("正しい日付を入力してください(yyyy-mm-dd)");
+            NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .expect("日付はyyyy-mm-ddの形式で入力してください");
+            value["date"] = Value::from(date);
+        }
+        Issue::ZeroPrice { .. } | Issue::AbsurdPrice { .. } => {
+            let price: u32 = input_line("正しい金額を入力してください")
+                .parse()
+                .expect("金額は数値で入力してください");
+            value["price"] = Value::from(price);
+        }
+        Issue::PlaceholderName { .. } => {
+            value["name"] = Value::from(input_line("正しい品目名を入力してください"));
+        }
+        Issue::UnknownCategory { .. } => {
+            let register_type: u8 = input_line("登録種別を入力してください(0: 収入, 1: 支出)")
+                .parse()
+                .expect("登録種別は数値で入力してください");
+            services::validate::InputValidator::validate_register_type(register_type);
+            let category_type: u8 = input_line("カテゴリーを入力してください(0, 1, 2)")
+                .parse()
+                .expect("カテゴリーは数値で入力してください");
+            services::validate::InputValidator::validate_category_type(
+                register_type,
+                category_type,
+            );
+            let category = models::Item::get_category(register_type, category_type);
+            value["category"] = serde_json::to_value(category).unwrap();
+        }
+        _ => println!("この問題は修正できないため削除またはスキップしてください"),
+    }
+}
+
+fn to_items(values: Vec<Value>) -> Option<Vec<models::Item>> {
+    values
+        .into_iter()
+        .map(|value| serde_json::from_value(value).ok())
+        .collect()
+}
+
+fn input_line(message: &str) -> String {
+    println!("{}", message);
+    let mut line = String::new();
+    io::stdin()
+        .read_line(&mut line)
+        .expect("入力に失敗しました");
+    line.trim().to_string()
+}
+
+#[cfg(test)]
+mod check_test {
+    use super::*;
+    use serde_json::json;
+
+    fn get_today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 8, 15).unwrap()
+    }
+
+    fn get_valid_value(id: u32) -> Value {
+        json!({
+            "id": id,
+            "name": "ランチ",
+            "category": { "Expense": "Food" },
+            "price": 800,
+            "date": "2025-08-01"
+        })
+    }
+
+    #[test]
+    fn test_find_issues_valid() {
+        let values = vec![get_valid_value(1), get_valid_value(2)];
+        assert!(find_issues(&values, &HashSet::new(), get_today()).is_empty());
+    }
+
+    #[test]
+    fn test_find_issues_current_store() {
+        // store/data.jsonと同じ内容
+        let values: Vec<Value> = serde_json::from_value(json!([
+            { "name": "1", "category": { "Expense": "Hobby" }, "price": 1, "date": "1111-11-11" },
+            { "name": "1", "category": { "Expense": "Hobby" }, "price": 111111, "date": "1111-11-11" },
+            { "name": "ボーナス", "category": { "Income": "Bonus" }, "price": 10000, "date": "2025-08-01" }
+        ]))
+        .unwrap();
+        let issues = find_issues(&values, &HashSet::new(), get_today());
+        let date = NaiveDate::from_ymd_opt(1111, 11, 11).unwrap();
+        assert!(issues.contains(&Issue::ImplausibleDate { index: 0, date }));
+        assert!(issues.contains(&Issue::PlaceholderName {
+            index: 1,
+            name: "1".to_string()
+        }));
+        assert!(issues.contains(&Issue::MissingId { index: 2 }));
+        assert_eq!(issues.len(), 7);
+    }
+
+    #[test]
+    fn test_find_issues_price() {
+        let mut zero = get_valid_value(1);
+        zero["price"] = json!(0);
+        let mut absurd = get_valid_value(2);
+        absurd["price"] = json!(50_000_000);
+
+        let issues = find_issues(&[zero, absurd], &HashSet::new(), get_today());
+        assert_eq!(
+            issues,
+            vec![
+                Issue::ZeroPrice { index: 0 },
+                Issue::AbsurdPrice {
+                    index: 1,
+                    price: 50_000_000
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_issues_future_date() {
+        let mut value = get_valid_value(1);
+        value["date"] = json!("2027-01-01");
+        let issues = find_issues(&[value], &HashSet::new(), get_today());
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(issues[0], Issue::ImplausibleDate { .. }));
+    }
+
+    #[test]
+    fn test_find_issues_schema() {
+        let mut unknown_category = get_valid_value(1);
+        unknown_category["category"] = json!({ "Expense": "Travel" });
+        let mut missing_price = get_valid_value(2);
+        missing_price.as_object_mut().unwrap().remove("price");
+
+        let issues = find_issues(
+            &[unknown_category, missing_price],
+            &HashSet::new(),
+            get_today(),
+        );
+        assert_eq!(
+            issues[0],
+            Issue::UnknownCategory {
+                index: 0,
+                category: "Expense:Travel".to_string()
+            }
+        );
+        assert!(matches!(issues[1], Issue::SchemaViolation { index: 1, .. }));
+    }
+
+    #[test]
+    fn test_find_issues_splits() {
+        let mut matched = get_valid_value(1);
+        matched["splits"] = json!([
+            { "category": { "Expense": "Food" }, "price": 500 },
+            { "category": { "Expense": "Hobby" }, "price": 300 }
+        ]);
+        let mut mismatched = get_valid_value(2);
+        mismatched["splits"] = json!([{ "category": { "Expense": "Food" }, "price": 500 }]);
+
+        let mut wrong_kind = get_valid_value(3);
+        wrong_kind["splits"] = json!([
+            { "category": { "Expense": "Food" }, "price": 800 },
+            { "category": { "Income": "Bonus" }, "price": 0 }
+        ]);
+
+        assert_eq!(
+            find_issues(
+                &[matched, mismatched, wrong_kind],
+                &HashSet::new(),
+                get_today()
+            ),
+            vec![
+                Issue::SplitMismatch {
+                    index: 1,
+                    sum: 500,
+                    price: 800
+                },
+                Issue::SplitKindMismatch {
+                    index: 2,
+                    category: "ボーナス".to_string()
+                },
+                Issue::ZeroSplitPrice { index: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_issues_duplicate_id() {
+        let values = vec![get_valid_value(1), get_valid_value(1)];
+        assert_eq!(
+            find_issues(&values, &HashSet::new(), get_today()),
+            vec![Issue::DuplicateId { index: 1, id: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_find_issues_archived_id() {
+        let values = vec![get_valid_value(1), get_valid_value(5)];
+        assert_eq!(
+            find_issues(&values, &HashSet::from([3, 5]), get_today()),
+            vec![Issue::DuplicateId { index: 1, id: 5 }]
+        );
+    }
+
+    #[test]
+    fn test_assign_ids_after_archives() {
+        let mut values = vec![get_valid_value(1), get_valid_value(0)];
+        assign_ids(&mut values, &[1], 10);
+        assert_eq!(values[1]["id"], json!(10));
+        assign_ids(&mut values, &[0], 1);
+        assert_eq!(values[0]["id"], json!(11));
+    }
+
+    #[test]
+    fn test_is_placeholder_name() {
+        assert!(is_placeholder_name("1"));
+        assert!(is_placeholder_name("123"));
+        assert!(is_placeholder_name(" "));
+        assert!(is_placeholder_name("a"));
+        assert!(!is_placeholder_name("本"));
+        assert!(!is_placeholder_name("ランチ"));
+        assert!(!is_placeholder_name("7-11"));
+    }
+
+    #[test]
+    fn test_apply_auto_fixes() {
+        let mut unknown_category = get_valid_value(3);
+        unknown_category["category"] = json!({ "Expense": "Travel" });
+        let mut values = vec![get_valid_value(3), unknown_category, get_valid_value(0)];
+        let issues = find_issues(&values, &HashSet::new(), get_today());
+        assert!(apply_auto_fixes(&mut values, &issues, 1));
+
+        assert!(find_issues(&values, &HashSet::new(), get_today()).is_empty());
+        assert_eq!(values[1]["id"], json!(4));
+        assert_eq!(values[1]["category"], json!({ "Expense": "Other" }));
+        assert_eq!(values[2]["id"], json!(5));
+        assert_eq!(to_items(values).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_apply_auto_fixes_unreadable() {
+        let mut missing_price = get_valid_value(0);
+        missing_price.as_object_mut().unwrap().remove("price");
+        let mut values = vec![get_valid_value(1), missing_price];
+        let before = values.clone();
+        let issues = find_issues(&values, &HashSet::new(), get_today());
+        // 形式が不正な項目があるとIDの採番も含めて何も変更しない
+        assert!(!apply_auto_fixes(&mut values, &issues, 1));
+        assert_eq!(values, before);
+    }
+}
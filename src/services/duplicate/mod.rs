@@ -0,0 +1,219 @@
+use crate::config::Config;
+use crate::{models, services};
+use chrono::Days;
+
+// 品目名の文字バイグラムがこの割合以上一致すれば似ているとみなす
+const SIMILARITY_THRESHOLD: f64 = 0.5;
+
+// 取り込む項目ごとに重複の可能性がある既存項目の位置を返す。既存項目は1件の取り込み項目にだけ対応させる
+pub fn find_duplicates(
+    items: &[models::Item],
+    existing: &[models::Item],
+    window_days: u32,
+) -> Vec<Option<usize>> {
+    let mut used = vec![false; existing.len()];
+    items
+        .iter()
+        .map(|item| {
+            let index = existing.iter().enumerate().position(|(index, other)| {
+                !used[index] && is_duplicate(item, other, window_days)
+            })?;
+            used[index] = true;
+            Some(index)
+        })
+        .collect()
+}
+
+// itemsの日付の前後window_days日を含む年度のアーカイブ済み項目を、重複の確認用に読み込む
+pub fn read_archived_candidates(items: &[models::Item], config: &Config) -> Vec<models::Item> {
+    let dates = items.iter().map(|item| item.get_date());
+    let (Some(from), Some(to)) = (dates.clone().min(), dates.max()) else {
+        return Vec::new();
+    };
+    let window = Days::new(config.duplicate.window_days as u64);
+    let from_year = config.get_fiscal_year(from - window);
+    let to_year = config.get_fiscal_year(to + window);
+    services::archive::read_archived_data(&config.ledger_path, |year| {
+        (from_year..=to_year).contains(&year)
+    })
+}
+
+// 取引IDが両方にあればその一致で判定する。
+// ない場合は同じ金額(収支の向きを含む)で、日付がwindow_days日以内、品目名が似ているものを重複とみなす
+pub fn is_duplicate(item: &models::Item, other: &models::Item, window_days: u32) -> bool {
+    if let (Some(id), Some(other_id)) = (item.get_external_id(), other.get_external_id()) {
+        return id == other_id;
+    }
+    item.get_price_for_summary() == other.get_price_for_summary()
+        && (item.get_date() - other.get_date())
+            .num_days()
+            .unsigned_abs()
+            <= window_days as u64
+        && is_similar_name(item.get_name(), other.get_name())
+}
+
+// 登録済みの項目を残し、取引ID・口座・支払い方法・照合済みの印のうち登録済みの側にないものだけ取り込んだ内容で補う。
+// 品目名やカテゴリーは手で整えたものを優先する
+pub fn merge(existing: &models::Item, item: models::Item) -> models::Item {
+    let payment_method = existing
+        .get_payment_method()
+        .or(item.get_payment_method())
+        .map(|payment_method| payment_method.to_string());
+    let account = existing
+        .get_account()
+        .or(item.get_account())
+        .map(|account| account.to_string());
+    let external_id = existing
+        .get_external_id()
+        .or(item.get_external_id())
+        .map(|external_id| external_id.to_string());
+    let cleared = existing.is_cleared() || item.is_cleared();
+    existing
+        .clone()
+        .with_payment_method(payment_method)
+        .with_account(account)
+        .with_external_id(external_id)
+        .with_cleared(cleared)
+}
+
+pub fn is_similar_name(name: &str, other: &str) -> bool {
+    let name = normalize(name);
+    let other = normalize(other);
+    if name.is_empty() || other.is_empty() {
+        return name == other;
+    }
+    if contains(&name, &other) || contains(&other, &name) {
+        return true;
+    }
+    get_similarity(&name, &other) >= SIMILARITY_THRESHOLD
+}
+
+// 全角英数字を半角に揃え、小文字化して記号と空白を除く
+fn normalize(name: &str) -> Vec<char> {
+    name.chars()
+        .map(|c| match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            _ => c,
+        })
+        .flat_map(|c| c.to_lowercase())
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+fn contains(name: &[char], part: &[char]) -> bool {
+    name.windows(part.len()).any(|window| window == part)
+}
+
+// バイグラムのDice係数
+fn get_similarity(name: &[char], other: &[char]) -> f64 {
+    let bigrams = |chars: &[char]| -> Vec<(char, char)> {
+        chars.windows(2).map(|pair| (pair[0], pair[1])).collect()
+    };
+    let name = bigrams(name);
+    let mut other = bigrams(other);
+    let total = name.len() + other.len();
+    if total == 0 {
+        return 0.0;
+    }
+    let mut matched = 0;
+    for bigram in &name {
+        if let Some(index) = other.iter().position(|candidate| candidate == bigram) {
+            other.swap_remove(index);
+            matched += 1;
+        }
+    }
+    (2 * matched) as f64 / total as f64
+}
+
+#[cfg(test)]
+mod duplicate_test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn get_test_item(name: &str, price: u32, day: u32) -> models::Item {
+        models::Item::new(
+            name.to_string(),
+            models::Category::Expense(models::ExpenseCategory::Food),
+            price,
+            NaiveDate::from_ymd_opt(2025, 8, day).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_is_similar_name() {
+        assert!(is_similar_name("AMAZON.CO.JP", "ａｍａｚｏｎ ｃｏ ｊｐ"));
+        assert!(is_similar_name("セブンイレブン新宿店", "セブンイレブン"));
+        assert!(is_similar_name("スターバックス 渋谷", "スターバックス新宿"));
+        assert!(!is_similar_name("ランチ", "書籍"));
+        assert!(!is_similar_name("", "ランチ"));
+    }
+
+    #[test]
+    fn test_is_duplicate() {
+        let item = get_test_item("ランチ", 800, 10);
+        assert!(is_duplicate(
+            &item,
+            &get_test_item("ランチ 新宿", 800, 12),
+            2
+        ));
+        assert!(!is_duplicate(&item, &get_test_item("ランチ", 800, 13), 2));
+        assert!(!is_duplicate(&item, &get_test_item("ランチ", 900, 10), 2));
+        let income = models::Item::new(
+            "ランチ".to_string(),
+            models::Category::Income(models::IncomeCategory::Other),
+            800,
+            NaiveDate::from_ymd_opt(2025, 8, 10).unwrap(),
+        );
+        assert!(!is_duplicate(&item, &income, 2));
+
+        let with_id =
+            |id: &str| get_test_item("ランチ", 800, 10).with_external_id(Some(id.to_string()));
+        assert!(is_duplicate(&with_id("A1"), &with_id("A1").with_id(3), 2));
+        assert!(!is_duplicate(&with_id("A1"), &with_id("A2"), 2));
+        assert!(is_duplicate(&with_id("A1"), &item, 2));
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let existing = vec![
+            get_test_item("ランチ", 800, 10).with_id(1),
+            get_test_item("書籍", 1500, 11).with_id(2),
+        ];
+        let items = vec![
+            get_test_item("ランチ", 800, 10),
+            get_test_item("ランチ", 800, 10),
+            get_test_item("書籍", 1500, 11),
+        ];
+        assert_eq!(
+            find_duplicates(&items, &existing, 2),
+            vec![Some(0), None, Some(1)]
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let existing = get_test_item("ランチ", 800, 10)
+            .with_id(3)
+            .with_payment_method(Some("現金".to_string()));
+        let imported = models::Item::new(
+            "ランチ 新宿店".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Other),
+            800,
+            NaiveDate::from_ymd_opt(2025, 8, 11).unwrap(),
+        )
+        .with_payment_method(Some("カード".to_string()))
+        .with_external_id(Some("T001".to_string()))
+        .with_cleared(true);
+        let merged = merge(&existing, imported);
+        assert_eq!(merged.get_id(), 3);
+        assert_eq!(merged.get_name(), "ランチ");
+        assert_eq!(
+            *merged.get_item_category(),
+            models::Category::Expense(models::ExpenseCategory::Food)
+        );
+        assert_eq!(merged.get_date(), existing.get_date());
+        assert_eq!(merged.get_payment_method(), Some("現金"));
+        assert_eq!(merged.get_external_id(), Some("T001"));
+        assert!(merged.is_cleared());
+    }
+}
@@ -0,0 +1,304 @@
+use crate::config::{Config, OutputFormat};
+use crate::models::{Category, ExpenseCategory};
+use crate::{models, services};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+pub const ENVELOPE_FILE: &str = "envelopes.json";
+
+// 収入を支出カテゴリーの封筒へ割り当てた記録。monthは月初の日付
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Assignment {
+    month: NaiveDate,
+    category: ExpenseCategory,
+    amount: i64,
+}
+
+// 封筒の残高は前月からの繰越(不足は負のまま繰り越す)に割り当てを足し、支出を引いたもの
+#[derive(Serialize, Debug, PartialEq)]
+pub struct EnvelopeStatus {
+    pub category: ExpenseCategory,
+    pub label: String,
+    pub carried: i64,
+    pub assigned: i64,
+    pub spent: i64,
+    pub balance: i64,
+}
+
+// unassignedはその月までの収入のうち、まだどの封筒にも割り当てていない金額
+#[derive(Serialize, Debug, PartialEq)]
+pub struct EnvelopeReport {
+    pub month: NaiveDate,
+    pub unassigned: i64,
+    pub envelopes: Vec<EnvelopeStatus>,
+}
+
+impl Assignment {
+    pub fn new(month: NaiveDate, category: ExpenseCategory, amount: i64) -> Self {
+        Assignment {
+            month,
+            category,
+            amount,
+        }
+    }
+}
+
+// 未割り当ては過去の収入から繰り越すため、monthまでの全期間を読み込む
+pub fn run(config: &Config, month: NaiveDate, output_format: OutputFormat) {
+    let data = services::io::read_data_between(config, None, Some(month));
+    let report = get_report(&data, &read_assignments(&config.ledger_path), month);
+    if output_format == OutputFormat::Json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("JSONに変換できませんでした")
+        );
+        return;
+    }
+    println!("{}年{}月の封筒", month.year(), month.month());
+    println!("未割り当て: {}", format(report.unassigned, config));
+    for status in &report.envelopes {
+        println!("{}", format_status(status, config));
+    }
+}
+
+// 負の金額を指定すると封筒から未割り当てに戻す
+pub fn run_assign(config: &Config, month: NaiveDate, category: &str, amount: &str) {
+    let category = ExpenseCategory::from_name(category)
+        .unwrap_or_else(|| panic!("支出のカテゴリーが不正です: {}", category));
+    let amount: i64 = amount.parse().expect("金額は数値で入力してください");
+    let mut assignments = read_assignments(&config.ledger_path);
+    assignments.push(Assignment::new(month, category, amount));
+    let message = format!(
+        "Assign envelope: {}-{:02} {} {}",
+        month.year(),
+        month.month(),
+        category.get_label(),
+        format(amount, config)
+    );
+    write_assignments(&assignments, config, &message);
+    println!(
+        "{}年{}月の{}に{}を割り当てました",
+        month.year(),
+        month.month(),
+        category.get_label(),
+        format(amount, config)
+    );
+
+    let data = services::io::read_data_between(config, None, Some(month));
+    let report = get_report(&data, &assignments, month);
+    if report.unassigned < 0 {
+        println!(
+            "収入より多く割り当てています: 未割り当て{}",
+            format(report.unassigned, config)
+        );
+    }
+}
+
+pub fn read_assignments(file_path: &str) -> Vec<Assignment> {
+    services::io::read_json_or_default(&services::io::get_sibling_path(file_path, ENVELOPE_FILE))
+}
+
+pub fn write_assignments(assignments: &[Assignment], config: &Config, message: &str) {
+    services::io::write_sidecar(&assignments, ENVELOPE_FILE, config, message);
+}
+
+// 月初の日付(month)の月末時点の封筒の状況
+pub fn get_report(
+    data: &[models::Item],
+    assignments: &[Assignment],
+    month: NaiveDate,
+) -> EnvelopeReport {
+    let income: i64 = data
+        .iter()
+        .filter(|item| item.get_first_day() <= month)
+        .filter(|item| matches!(item.get_item_category(), Category::Income(_)))
+        .map(|item| item.get_price() as i64)
+        .sum();
+    let assigned: i64 = assignments
+        .iter()
+        .filter(|assignment| assignment.month <= month)
+        .map(|assignment| assignment.amount)
+        .sum();
+
+    // 割り当てのないカテゴリーでも支出があれば不足として表示する
+    let categories: BTreeSet<ExpenseCategory> = assignments
+        .iter()
+        .filter(|assignment| assignment.month <= month)
+        .map(|assignment| assignment.category)
+        .chain(
+            data.iter()
+                .filter(|item| item.get_first_day() <= month)
+                .flat_map(|item| item.get_category_prices())
+                .filter_map(|(category, _)| match category {
+                    Category::Expense(category) => Some(category),
+                    _ => None,
+                }),
+        )
+        .collect();
+    let envelopes = categories
+        .into_iter()
+        .map(|category| {
+            let assigned_before = get_assigned(assignments, category, |date| date < month);
+            let spent_before = get_spent(data, category, |date| date < month);
+            let assigned = get_assigned(assignments, category, |date| date == month);
+            let spent = get_spent(data, category, |date| date == month);
+            let carried = assigned_before - spent_before;
+            EnvelopeStatus {
+                category,
+                label: category.get_label().to_string(),
+                carried,
+                assigned,
+                spent,
+                balance: carried + assigned - spent,
+            }
+        })
+        .collect();
+
+    EnvelopeReport {
+        month,
+        unassigned: income - assigned,
+        envelopes,
+    }
+}
+
+pub fn format_status(status: &EnvelopeStatus, config: &Config) -> String {
+    let shortage = if status.balance < 0 { "(不足)" } else { "" };
+    format!(
+        "{}: 繰越{} 割り当て{} 支出{} 残高{}{}",
+        status.label,
+        format(status.carried, config),
+        format(status.assigned, config),
+        format(status.spent, config),
+        format(status.balance, config),
+        shortage
+    )
+}
+
+fn format(amount: i64, config: &Config) -> String {
+    config.currency.format(amount)
+}
+
+fn get_assigned<F: Fn(NaiveDate) -> bool>(
+    assignments: &[Assignment],
+    category: ExpenseCategory,
+    filter: F,
+) -> i64 {
+    assignments
+        .iter()
+        .filter(|assignment| assignment.category == category && filter(assignment.month))
+        .map(|assignment| assignment.amount)
+        .sum()
+}
+
+fn get_spent<F: Fn(NaiveDate) -> bool>(
+    data: &[models::Item],
+    category: ExpenseCategory,
+    filter: F,
+) -> i64 {
+    data.iter()
+        .filter(|item| filter(item.get_first_day()))
+        .flat_map(|item| item.get_category_prices())
+        .filter(|(item_category, _)| *item_category == Category::Expense(category))
+        .map(|(_, price)| price as i64)
+        .sum()
+}
+
+#[cfg(test)]
+mod envelope_test {
+    use super::*;
+    use std::fs;
+
+    fn get_date(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    fn get_test_item(category: Category, price: u32, date: &str) -> models::Item {
+        models::Item::new("テスト".to_string(), category, price, get_date(date))
+    }
+
+    fn get_test_data() -> Vec<models::Item> {
+        let salary = Category::Income(models::IncomeCategory::Salary);
+        let food = Category::Expense(ExpenseCategory::Food);
+        let hobby = Category::Expense(ExpenseCategory::Hobby);
+        let other = Category::Expense(ExpenseCategory::Other);
+        vec![
+            get_test_item(salary, 300000, "2025-07-25"),
+            get_test_item(food, 40000, "2025-07-10"),
+            get_test_item(hobby, 25000, "2025-07-15"),
+            get_test_item(salary, 300000, "2025-08-25"),
+            get_test_item(food, 30000, "2025-08-10"),
+            get_test_item(other, 1000, "2025-08-12"),
+        ]
+    }
+
+    fn get_test_assignments() -> Vec<Assignment> {
+        vec![
+            Assignment::new(get_date("2025-07-01"), ExpenseCategory::Food, 50000),
+            Assignment::new(get_date("2025-07-01"), ExpenseCategory::Hobby, 20000),
+            Assignment::new(get_date("2025-08-01"), ExpenseCategory::Food, 50000),
+            Assignment::new(get_date("2025-08-01"), ExpenseCategory::Hobby, 20000),
+        ]
+    }
+
+    #[test]
+    fn test_get_report() {
+        let report = get_report(
+            &get_test_data(),
+            &get_test_assignments(),
+            get_date("2025-08-01"),
+        );
+        assert_eq!(report.unassigned, 460000);
+        assert_eq!(
+            report.envelopes[0],
+            EnvelopeStatus {
+                category: ExpenseCategory::Food,
+                label: "食費".to_string(),
+                carried: 10000,
+                assigned: 50000,
+                spent: 30000,
+                balance: 30000,
+            }
+        );
+        // 前月の不足は負のまま繰り越す
+        assert_eq!(report.envelopes[1].carried, -5000);
+        assert_eq!(report.envelopes[1].balance, 15000);
+        // 割り当てのない支出は不足になる
+        assert_eq!(report.envelopes[2].category, ExpenseCategory::Other);
+        assert_eq!(report.envelopes[2].balance, -1000);
+        assert_eq!(
+            format_status(&report.envelopes[2], &Config::default()),
+            "その他: 繰越0円 割り当て0円 支出1000円 残高-1000円(不足)"
+        );
+    }
+
+    #[test]
+    fn test_get_report_before_assignments() {
+        let report = get_report(
+            &get_test_data(),
+            &get_test_assignments(),
+            get_date("2025-06-01"),
+        );
+        assert_eq!(report.unassigned, 0);
+        assert!(report.envelopes.is_empty());
+    }
+
+    #[test]
+    fn test_read_write_assignments() {
+        let test_dir = std::env::temp_dir().join("kakeibo_envelope_test");
+        fs::create_dir_all(&test_dir).unwrap();
+        let ledger_path = test_dir.join("data.json");
+        let mut config = Config {
+            ledger_path: ledger_path.to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+        config.backup.enabled = false;
+        let ledger_path = config.ledger_path.as_str();
+        assert!(read_assignments(ledger_path).is_empty());
+
+        write_assignments(&get_test_assignments(), &config, "Assign envelope");
+        assert_eq!(read_assignments(ledger_path), get_test_assignments());
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+}
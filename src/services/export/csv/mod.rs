@@ -0,0 +1,37 @@
+// 表をCSV/TSVの文字列にする。区切り文字や改行を含む値は引用符で囲む
+pub fn write(table: &[Vec<String>], delimiter: u8) -> String {
+    let mut writer = ::csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(Vec::new());
+    for row in table {
+        writer
+            .write_record(row)
+            .expect("CSVの書き込みに失敗しました");
+    }
+    let bytes = writer.into_inner().expect("CSVの書き込みに失敗しました");
+    String::from_utf8(bytes).expect("CSVの書き込みに失敗しました")
+}
+
+#[cfg(test)]
+mod csv_test {
+    use super::*;
+
+    fn get_table() -> Vec<Vec<String>> {
+        vec![
+            vec!["品目".to_string(), "金額".to_string()],
+            vec!["ランチ, 新宿".to_string(), "800".to_string()],
+        ]
+    }
+
+    #[test]
+    fn test_write() {
+        assert_eq!(
+            write(&get_table(), b','),
+            "品目,金額\n\"ランチ, 新宿\",800\n"
+        );
+        assert_eq!(
+            write(&get_table(), b'\t'),
+            "品目\t金額\nランチ, 新宿\t800\n"
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use crate::models;
+use std::collections::BTreeSet;
+
+// 収支の相手となる資産の勘定
+pub const ASSET_ACCOUNT: &str = "Assets:Cash";
+pub const COMMODITY: &str = "JPY";
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum JournalSyntax {
+    Beancount,
+    Hledger,
+}
+
+// カテゴリーを Expenses:Food や Income:Salary のような勘定名にする
+pub fn get_account(category: &models::Category) -> String {
+    match category {
+        models::Category::Income(category) => format!("Income:{:?}", category),
+        models::Category::Expense(category) => format!("Expenses:{:?}", category),
+    }
+}
+
+pub fn write(data: &[models::Item], syntax: JournalSyntax) -> String {
+    let mut lines = Vec::new();
+    if syntax == JournalSyntax::Beancount {
+        lines.push(format!("option \"operating_currency\" \"{}\"", COMMODITY));
+        lines.push(String::new());
+        // beancountは使う勘定を最初の取引より前に開いておく必要がある
+        if let Some(first) = data.iter().map(|item| item.get_date()).min() {
+            let accounts: BTreeSet<String> = std::iter::once(ASSET_ACCOUNT.to_string())
+                .chain(
+                    data.iter()
+                        .flat_map(|item| item.get_category_prices())
+                        .map(|(category, _)| get_account(&category)),
+                )
+                .collect();
+            for account in accounts {
+                lines.push(format!("{} open {} {}", first, account, COMMODITY));
+            }
+            lines.push(String::new());
+        }
+    } else {
+        lines.push(format!("commodity 1000 {}", COMMODITY));
+        lines.push(String::new());
+    }
+    for item in data {
+        lines.extend(write_transaction(item, syntax));
+        lines.push(String::new());
+    }
+    lines.join("\n")
+}
+
+// 収入は Income を貸方(負)、支出は Expenses を借方(正)にして資産の勘定と釣り合わせる。
+// 内訳のある項目は内訳ごとに記帳する
+fn write_transaction(item: &models::Item, syntax: JournalSyntax) -> Vec<String> {
+    let amount = -item.get_price_for_summary();
+    let posting =
+        |account: &str, amount: i32| format!("  {:<24}{:>12} {}", account, amount, COMMODITY);
+    let postings: Vec<String> = item
+        .get_category_prices()
+        .into_iter()
+        .map(|(category, price)| {
+            let amount = match category {
+                models::Category::Income(_) => -(price as i32),
+                models::Category::Expense(_) => price as i32,
+            };
+            posting(&get_account(&category), amount)
+        })
+        .collect();
+    match syntax {
+        JournalSyntax::Beancount => {
+            let mut lines = vec![
+                format!("{} * \"{}\"", item.get_date(), escape(item.get_name())),
+                format!("  id: \"{}\"", item.get_id()),
+            ];
+            if let Some(payment_method) = item.get_payment_method() {
+                lines.push(format!("  payment_method: \"{}\"", escape(payment_method)));
+            }
+            lines.extend(postings);
+            lines.push(posting(ASSET_ACCOUNT, -amount));
+            lines
+        }
+        JournalSyntax::Hledger => {
+            let mut tags = vec![format!("id:{}", item.get_id())];
+            if let Some(payment_method) = item.get_payment_method() {
+                tags.push(format!("payment_method:{}", sanitize(payment_method)));
+            }
+            let mut lines = vec![format!(
+                "{} {}  ; {}",
+                item.get_date(),
+                sanitize(item.get_name()),
+                tags.join(", ")
+            )];
+            lines.extend(postings);
+            lines.push(posting(ASSET_ACCOUNT, -amount));
+            lines
+        }
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// hledgerはタグの値を引用できず、`,`で値が終わり`;`からはコメントになるため空白に置き換える
+fn sanitize(value: &str) -> String {
+    value
+        .replace([',', ';', '\n', '\r'], " ")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod journal_test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn get_test_data() -> Vec<models::Item> {
+        vec![
+            models::Item::new(
+                "給料".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                300000,
+                NaiveDate::from_ymd_opt(2025, 8, 25).unwrap(),
+            )
+            .with_id(1),
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                800,
+                NaiveDate::from_ymd_opt(2025, 8, 26).unwrap(),
+            )
+            .with_id(2)
+            .with_payment_method(Some("現金".to_string())),
+        ]
+    }
+
+    #[test]
+    fn test_write_beancount() {
+        let journal = write(&get_test_data(), JournalSyntax::Beancount);
+        assert!(journal.starts_with("option \"operating_currency\" \"JPY\"\n"));
+        assert!(journal.contains("2025-08-25 open Assets:Cash JPY\n"));
+        assert!(journal.contains("2025-08-25 open Expenses:Food JPY\n"));
+        assert!(journal.contains(
+            "2025-08-26 * \"ランチ\"\n  id: \"2\"\n  payment_method: \"現金\"\n  Expenses:Food                    800 JPY\n  Assets:Cash                     -800 JPY\n"
+        ));
+        assert!(journal.contains("  Income:Salary                -300000 JPY\n"));
+    }
+
+    #[test]
+    fn test_write_hledger() {
+        let journal = write(&get_test_data(), JournalSyntax::Hledger);
+        assert!(journal.contains(
+            "2025-08-26 ランチ  ; id:2, payment_method:現金\n  Expenses:Food                    800 JPY\n"
+        ));
+        assert!(!journal.contains("open"));
+    }
+
+    #[test]
+    fn test_write_hledger_sanitize() {
+        let data = vec![
+            models::Item::new(
+                "ランチ;同僚と".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                800,
+                NaiveDate::from_ymd_opt(2025, 8, 26).unwrap(),
+            )
+            .with_id(2)
+            .with_payment_method(Some("カード,家族;共有".to_string())),
+        ];
+        let journal = write(&data, JournalSyntax::Hledger);
+        assert!(
+            journal.contains("2025-08-26 ランチ 同僚と  ; id:2, payment_method:カード 家族 共有\n")
+        );
+    }
+}
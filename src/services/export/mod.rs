@@ -0,0 +1,297 @@
+pub mod csv;
+pub mod journal;
+pub mod ofx;
+pub mod qif;
+pub mod xlsx;
+
+use crate::config::Config;
+use crate::services::io::TextEncoding;
+use crate::{models, services};
+use chrono::Local;
+use std::fs;
+use std::io::{self, Write};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ExportFormat {
+    Csv,
+    Tsv,
+    Ofx,
+    Qif,
+    Beancount,
+    Hledger,
+    Xlsx,
+}
+
+// 出力する表。summaryはsummarize、yearly/monthlyはstatisticsと同じ集計
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ExportTarget {
+    Items,
+    Summary,
+    Yearly,
+    Monthly,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    pub target: ExportTarget,
+    pub encoding: TextEncoding,
+    pub file: Option<String>,
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "csv" => Some(ExportFormat::Csv),
+            "tsv" => Some(ExportFormat::Tsv),
+            "ofx" => Some(ExportFormat::Ofx),
+            "qif" => Some(ExportFormat::Qif),
+            "beancount" => Some(ExportFormat::Beancount),
+            "hledger" => Some(ExportFormat::Hledger),
+            "xlsx" => Some(ExportFormat::Xlsx),
+            _ => None,
+        }
+    }
+}
+
+impl ExportTarget {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "items" => Some(ExportTarget::Items),
+            "summary" => Some(ExportTarget::Summary),
+            "yearly" => Some(ExportTarget::Yearly),
+            "monthly" => Some(ExportTarget::Monthly),
+            _ => None,
+        }
+    }
+}
+
+// 全期間を書き出すため、アーカイブもすべて読み込む
+pub fn run(config: &Config, options: &ExportOptions) {
+    let data = services::io::read_data_with_archives_or_panic(&config.ledger_path);
+    let bytes = match options.format {
+        // XLSXは明細・集計をまとめたブックなので出力対象は問わない
+        ExportFormat::Xlsx if options.file.is_none() => {
+            panic!("XLSX形式では--fileを指定してください")
+        }
+        ExportFormat::Xlsx => xlsx::write(&data, config),
+        ExportFormat::Csv => {
+            let table = get_table(&data, options.target, config);
+            options.encoding.encode(&csv::write(&table, b','))
+        }
+        ExportFormat::Tsv => {
+            let table = get_table(&data, options.target, config);
+            options.encoding.encode(&csv::write(&table, b'\t'))
+        }
+        _ if options.target != ExportTarget::Items => {
+            panic!("CSV/TSV以外の形式で書き出せるのはitemsだけです")
+        }
+        ExportFormat::Ofx => options
+            .encoding
+            .encode(&ofx::write(&data, Local::now().date_naive())),
+        ExportFormat::Qif => options.encoding.encode(&qif::write(&data)),
+        ExportFormat::Beancount => options
+            .encoding
+            .encode(&journal::write(&data, journal::JournalSyntax::Beancount)),
+        ExportFormat::Hledger => options
+            .encoding
+            .encode(&journal::write(&data, journal::JournalSyntax::Hledger)),
+    };
+    write_output(&bytes, options.file.as_deref());
+}
+
+// 出力先のファイルが指定されていなければ標準出力に書く
+pub fn write_output(bytes: &[u8], file: Option<&str>) {
+    match file {
+        Some(path) => {
+            fs::write(path, bytes).expect("出力ファイルの書き込みに失敗しました");
+            println!("{}に出力しました", path);
+        }
+        None => io::stdout()
+            .write_all(bytes)
+            .expect("標準出力への書き込みに失敗しました"),
+    }
+}
+
+// 1行目を見出しとした表。金額は表計算ソフトで扱えるよう通貨記号を付けない
+pub fn get_table(data: &[models::Item], target: ExportTarget, config: &Config) -> Vec<Vec<String>> {
+    let header = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+    match target {
+        ExportTarget::Items => std::iter::once(header(&[
+            "ID",
+            "日付",
+            "品目",
+            "収支",
+            "カテゴリー",
+            "金額",
+            "支払方法",
+            "口座",
+            "内訳",
+        ]))
+        .chain(data.iter().map(|item| {
+            vec![
+                item.get_id().to_string(),
+                item.get_date().to_string(),
+                item.get_name().to_string(),
+                get_kind_label(item.get_item_category()).to_string(),
+                item.get_item_category().get_label().to_string(),
+                item.get_price().to_string(),
+                item.get_payment_method().unwrap_or("").to_string(),
+                item.get_account().unwrap_or("").to_string(),
+                get_splits_label(item),
+            ]
+        }))
+        .collect(),
+        ExportTarget::Summary => std::iter::once(header(&["年月", "収支"]))
+            .chain(
+                services::summarize::get_monthly_table(data)
+                    .into_iter()
+                    .map(|(date, price)| vec![date.format("%Y-%m").to_string(), price.to_string()]),
+            )
+            .collect(),
+        ExportTarget::Yearly => {
+            let year_header = if config.fiscal_year_start == 1 {
+                "年"
+            } else {
+                "年度"
+            };
+            std::iter::once(header(&[year_header, "収支"]))
+                .chain(
+                    services::statistics::get_yearly_statistics(data, config)
+                        .into_iter()
+                        .map(|(year, price)| vec![year.to_string(), price.to_string()]),
+                )
+                .collect()
+        }
+        ExportTarget::Monthly => std::iter::once(header(&["月", "収支"]))
+            .chain(
+                services::statistics::get_monthly_statistics(data)
+                    .into_iter()
+                    .map(|(month, price)| vec![month.to_string(), price.to_string()]),
+            )
+            .collect(),
+    }
+}
+
+// 内訳を "食費:2200 趣味:800" の形式にする。内訳がなければ空
+pub fn get_splits_label(item: &models::Item) -> String {
+    item.get_splits()
+        .iter()
+        .map(|split| format!("{}:{}", split.get_category().get_label(), split.get_price()))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn get_kind_label(category: &models::Category) -> &str {
+    match category {
+        models::Category::Income(_) => "収入",
+        models::Category::Expense(_) => "支出",
+    }
+}
+
+#[cfg(test)]
+mod export_test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn get_test_data() -> Vec<models::Item> {
+        vec![
+            models::Item::new(
+                "給料".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                300000,
+                NaiveDate::from_ymd_opt(2025, 3, 25).unwrap(),
+            )
+            .with_id(1),
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                800,
+                NaiveDate::from_ymd_opt(2025, 4, 1).unwrap(),
+            )
+            .with_id(2)
+            .with_payment_method(Some("現金".to_string())),
+        ]
+    }
+
+    #[test]
+    fn test_get_table_items() {
+        let mut data = get_test_data();
+        data.push(
+            models::Item::new(
+                "スーパー".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                3000,
+                NaiveDate::from_ymd_opt(2025, 4, 2).unwrap(),
+            )
+            .with_id(3)
+            .with_splits(vec![
+                models::Split::new(
+                    models::Category::Expense(models::ExpenseCategory::Food),
+                    2200,
+                ),
+                models::Split::new(
+                    models::Category::Expense(models::ExpenseCategory::Hobby),
+                    800,
+                ),
+            ]),
+        );
+        let table = get_table(&data, ExportTarget::Items, &Config::default());
+        assert_eq!(table.len(), 4);
+        assert_eq!(
+            table[2],
+            vec![
+                "2",
+                "2025-04-01",
+                "ランチ",
+                "支出",
+                "食費",
+                "800",
+                "現金",
+                "",
+                ""
+            ]
+        );
+        assert_eq!(table[3][8], "食費:2200 趣味:800");
+    }
+
+    #[test]
+    fn test_get_table_reports() {
+        let mut config = Config::default();
+        let table = get_table(&get_test_data(), ExportTarget::Summary, &config);
+        assert_eq!(
+            table,
+            vec![
+                vec!["年月", "収支"],
+                vec!["2025-03", "300000"],
+                vec!["2025-04", "-800"],
+            ]
+        );
+
+        config.fiscal_year_start = 4;
+        let table = get_table(&get_test_data(), ExportTarget::Yearly, &config);
+        assert_eq!(
+            table,
+            vec![
+                vec!["年度", "収支"],
+                vec!["2024", "300000"],
+                vec!["2025", "-800"],
+            ]
+        );
+
+        let table = get_table(&get_test_data(), ExportTarget::Monthly, &config);
+        assert_eq!(table[1], vec!["3", "300000"]);
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(ExportFormat::from_name("tsv"), Some(ExportFormat::Tsv));
+        assert_eq!(ExportFormat::from_name("xls"), None);
+        // commodity宣言がhledger独自の書式のため、ledgerとしては受け付けない
+        assert_eq!(ExportFormat::from_name("ledger"), None);
+        assert_eq!(
+            ExportTarget::from_name("summary"),
+            Some(ExportTarget::Summary)
+        );
+    }
+}
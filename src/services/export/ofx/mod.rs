@@ -0,0 +1,148 @@
+use crate::models;
+use chrono::NaiveDate;
+
+// OFX 2.x(XML)の銀行明細として書き出す。FITIDは取り込み元のIDがあればそれを、なければ項目のIDから作る。
+// IDが未設定(0)の項目は日付・明細内の位置・金額から作る(すべてkakeibo-0になると取り込み時に重複とみなされるため)
+pub fn write(data: &[models::Item], today: NaiveDate) -> String {
+    let format_date = |date: NaiveDate| date.format("%Y%m%d").to_string();
+    let start = data
+        .iter()
+        .map(|item| item.get_date())
+        .min()
+        .unwrap_or(today);
+    let end = data
+        .iter()
+        .map(|item| item.get_date())
+        .max()
+        .unwrap_or(today);
+    let balance: i32 = data.iter().map(|item| item.get_price_for_summary()).sum();
+
+    let mut lines = vec![
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#.to_string(),
+        r#"<?OFX OFXHEADER="200" VERSION="211" SECURITY="NONE" OLDFILEUID="NONE" NEWFILEUID="NONE"?>"#.to_string(),
+        "<OFX>".to_string(),
+        "<SIGNONMSGSRSV1><SONRS>".to_string(),
+        "<STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>".to_string(),
+        format!("<DTSERVER>{}</DTSERVER><LANGUAGE>JPN</LANGUAGE>", format_date(today)),
+        "</SONRS></SIGNONMSGSRSV1>".to_string(),
+        "<BANKMSGSRSV1><STMTTRNRS><TRNUID>0</TRNUID>".to_string(),
+        "<STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>".to_string(),
+        "<STMTRS><CURDEF>JPY</CURDEF>".to_string(),
+        "<BANKACCTFROM><BANKID>0000</BANKID><ACCTID>kakeibo</ACCTID><ACCTTYPE>CHECKING</ACCTTYPE></BANKACCTFROM>".to_string(),
+        format!(
+            "<BANKTRANLIST><DTSTART>{}</DTSTART><DTEND>{}</DTEND>",
+            format_date(start),
+            format_date(end)
+        ),
+    ];
+    for (index, item) in data.iter().enumerate() {
+        let kind = match item.get_item_category() {
+            models::Category::Income(_) => "CREDIT",
+            models::Category::Expense(_) => "DEBIT",
+        };
+        let fitid = match (item.get_external_id(), item.get_id()) {
+            (Some(fitid), _) => fitid.to_string(),
+            (None, 0) => format!(
+                "kakeibo-{}-{}-{}",
+                format_date(item.get_date()),
+                index,
+                item.get_price()
+            ),
+            (None, id) => format!("kakeibo-{}", id),
+        };
+        lines.push(format!(
+            "<STMTTRN><TRNTYPE>{}</TRNTYPE><DTPOSTED>{}</DTPOSTED><TRNAMT>{}</TRNAMT><FITID>{}</FITID><NAME>{}</NAME><MEMO>{}</MEMO></STMTTRN>",
+            kind,
+            format_date(item.get_date()),
+            item.get_price_for_summary(),
+            escape(&fitid),
+            escape(item.get_name()),
+            escape(item.get_item_category().get_label())
+        ));
+    }
+    lines.extend([
+        "</BANKTRANLIST>".to_string(),
+        format!(
+            "<LEDGERBAL><BALAMT>{}</BALAMT><DTASOF>{}</DTASOF></LEDGERBAL>",
+            balance,
+            format_date(end)
+        ),
+        "</STMTRS></STMTTRNRS></BANKMSGSRSV1>".to_string(),
+        "</OFX>".to_string(),
+    ]);
+    lines.join("\n") + "\n"
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod ofx_test {
+    use super::*;
+    use crate::services::import;
+
+    #[test]
+    fn test_write_and_parse() {
+        let data = vec![
+            models::Item::new(
+                "ランチ & カフェ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                800,
+                NaiveDate::from_ymd_opt(2025, 8, 1).unwrap(),
+            )
+            .with_id(1),
+            models::Item::new(
+                "給料".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                300000,
+                NaiveDate::from_ymd_opt(2025, 8, 25).unwrap(),
+            )
+            .with_id(2)
+            .with_external_id(Some("BANK-1".to_string())),
+        ];
+        let ofx = write(&data, NaiveDate::from_ymd_opt(2025, 9, 1).unwrap());
+        assert!(ofx.contains("<DTSTART>20250801</DTSTART><DTEND>20250825</DTEND>"));
+        assert!(ofx.contains("<BALAMT>299200</BALAMT>"));
+
+        let result = import::ofx::parse(ofx.as_bytes());
+        assert!(result.errors.is_empty());
+        assert_eq!(result.items[0].get_name(), "ランチ & カフェ");
+        assert_eq!(result.items[0].get_external_id(), Some("kakeibo-1"));
+        assert_eq!(
+            *result.items[0].get_item_category(),
+            *data[0].get_item_category()
+        );
+        assert_eq!(result.items[1].get_external_id(), Some("BANK-1"));
+        assert_eq!(
+            *result.items[1].get_item_category(),
+            *data[1].get_item_category()
+        );
+    }
+
+    #[test]
+    fn test_write_without_id() {
+        let item = models::Item::new(
+            "ランチ".to_string(),
+            models::Category::Expense(models::ExpenseCategory::Food),
+            800,
+            NaiveDate::from_ymd_opt(2025, 8, 1).unwrap(),
+        );
+        let ofx = write(
+            &[item.clone(), item],
+            NaiveDate::from_ymd_opt(2025, 9, 1).unwrap(),
+        );
+        let result = import::ofx::parse(ofx.as_bytes());
+        assert_eq!(
+            result.items[0].get_external_id(),
+            Some("kakeibo-20250801-0-800")
+        );
+        assert_eq!(
+            result.items[1].get_external_id(),
+            Some("kakeibo-20250801-1-800")
+        );
+    }
+}
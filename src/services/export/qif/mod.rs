@@ -0,0 +1,50 @@
+use crate::models;
+
+// 銀行口座(!Type:Bank)の取引として書き出す。日付は多くのソフトが読める MM/DD/YYYY にする
+pub fn write(data: &[models::Item]) -> String {
+    let mut lines = vec!["!Type:Bank".to_string()];
+    for item in data {
+        lines.push(format!("D{}", item.get_date().format("%m/%d/%Y")));
+        lines.push(format!("T{}", item.get_price_for_summary()));
+        lines.push(format!("P{}", item.get_name()));
+        lines.push(format!("L{}", item.get_item_category().get_label()));
+        if let Some(payment_method) = item.get_payment_method() {
+            lines.push(format!("M{}", payment_method));
+        }
+        lines.push("^".to_string());
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod qif_test {
+    use super::*;
+    use crate::services::import;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_write_and_parse() {
+        let data = vec![
+            models::Item::new(
+                "書店".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Hobby),
+                1500,
+                NaiveDate::from_ymd_opt(2025, 8, 3).unwrap(),
+            )
+            .with_payment_method(Some("現金".to_string())),
+        ];
+        let qif = write(&data);
+        assert_eq!(
+            qif,
+            "!Type:Bank\nD08/03/2025\nT-1500\nP書店\nL趣味\nM現金\n^\n"
+        );
+
+        let result = import::qif::parse(qif.as_bytes());
+        assert_eq!(result.items[0].get_name(), "書店");
+        assert_eq!(result.items[0].get_date(), data[0].get_date());
+        assert_eq!(
+            *result.items[0].get_item_category(),
+            *data[0].get_item_category()
+        );
+    }
+}
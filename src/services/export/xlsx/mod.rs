@@ -0,0 +1,228 @@
+use crate::config::{Config, CurrencyFormat, SymbolPosition};
+use crate::{models, services};
+use chrono::NaiveDate;
+use rust_xlsxwriter::{Format, FormatBorder, Formula, Workbook, Worksheet, XlsxError};
+use std::collections::BTreeSet;
+
+struct Formats {
+    header: Format,
+    date: Format,
+    month: Format,
+    month_header: Format,
+    money: Format,
+    total: Format,
+}
+
+// 明細・月次集計・カテゴリー別の3シートのブックを作る。日付は日付型、金額は通貨書式の数値にする
+pub fn write(data: &[models::Item], config: &Config) -> Vec<u8> {
+    build(data, config).expect("XLSXファイルの作成に失敗しました")
+}
+
+fn build(data: &[models::Item], config: &Config) -> Result<Vec<u8>, XlsxError> {
+    let money = get_number_format(&config.currency);
+    let formats = Formats {
+        header: Format::new()
+            .set_bold()
+            .set_border_bottom(FormatBorder::Thin),
+        date: Format::new().set_num_format("yyyy-mm-dd"),
+        month: Format::new().set_num_format("yyyy/mm"),
+        month_header: Format::new()
+            .set_bold()
+            .set_num_format("yyyy/mm")
+            .set_border_bottom(FormatBorder::Thin),
+        money: Format::new().set_num_format(&money),
+        total: Format::new()
+            .set_bold()
+            .set_num_format(&money)
+            .set_border_top(FormatBorder::Thin),
+    };
+    let mut workbook = Workbook::new();
+    write_items_sheet(workbook.add_worksheet(), data, &formats)?;
+    write_summary_sheet(workbook.add_worksheet(), data, &formats)?;
+    write_category_sheet(workbook.add_worksheet(), data, &formats)?;
+    workbook.save_to_buffer()
+}
+
+fn write_items_sheet(
+    sheet: &mut Worksheet,
+    data: &[models::Item],
+    formats: &Formats,
+) -> Result<(), XlsxError> {
+    sheet.set_name("明細")?;
+    let headers = [
+        "ID",
+        "日付",
+        "品目",
+        "収支",
+        "カテゴリー",
+        "金額",
+        "支払方法",
+        "口座",
+        "内訳",
+    ];
+    sheet.write_row_with_format(0, 0, headers, &formats.header)?;
+    for (row, item) in (1..).zip(data) {
+        let kind = match item.get_item_category() {
+            models::Category::Income(_) => "収入",
+            models::Category::Expense(_) => "支出",
+        };
+        sheet.write_number(row, 0, item.get_id())?;
+        sheet.write_date_with_format(row, 1, item.get_date(), &formats.date)?;
+        sheet.write_string(row, 2, item.get_name())?;
+        sheet.write_string(row, 3, kind)?;
+        sheet.write_string(row, 4, item.get_item_category().get_label())?;
+        sheet.write_number_with_format(row, 5, item.get_price_for_summary(), &formats.money)?;
+        sheet.write_string(row, 6, item.get_payment_method().unwrap_or(""))?;
+        sheet.write_string(row, 7, item.get_account().unwrap_or(""))?;
+        sheet.write_string(row, 8, super::get_splits_label(item))?;
+    }
+    sheet.autofilter(0, 0, data.len() as u32, headers.len() as u16 - 1)?;
+    sheet.set_freeze_panes(1, 0)?;
+    sheet.autofit();
+    Ok(())
+}
+
+// summarizeと同じ月ごとの収支に、収入・支出の内訳と合計行を加える
+fn write_summary_sheet(
+    sheet: &mut Worksheet,
+    data: &[models::Item],
+    formats: &Formats,
+) -> Result<(), XlsxError> {
+    sheet.set_name("月次集計")?;
+    sheet.write_row_with_format(0, 0, ["年月", "収入", "支出", "収支"], &formats.header)?;
+    let category_table = services::summarize::get_category_table(data);
+    let monthly_table = services::summarize::get_monthly_table(data);
+    let mut row = 1;
+    for (month, balance) in &monthly_table {
+        let (income, expense) =
+            category_table[month]
+                .iter()
+                .fold(
+                    (0, 0),
+                    |(income, expense), (category, price)| match category {
+                        models::Category::Income(_) => (income + price, expense),
+                        models::Category::Expense(_) => (income, expense + price),
+                    },
+                );
+        sheet.write_date_with_format(row, 0, month, &formats.month)?;
+        sheet.write_number_with_format(row, 1, income, &formats.money)?;
+        sheet.write_number_with_format(row, 2, expense, &formats.money)?;
+        sheet.write_number_with_format(row, 3, *balance, &formats.money)?;
+        row += 1;
+    }
+    write_total_row(sheet, row, 1..=3, formats)?;
+    sheet.set_freeze_panes(1, 0)?;
+    sheet.autofit();
+    Ok(())
+}
+
+// 行がカテゴリー、列が月のピボット
+fn write_category_sheet(
+    sheet: &mut Worksheet,
+    data: &[models::Item],
+    formats: &Formats,
+) -> Result<(), XlsxError> {
+    sheet.set_name("カテゴリー別")?;
+    let table = services::summarize::get_category_table(data);
+    let months: Vec<&NaiveDate> = table.keys().collect();
+    let categories: BTreeSet<&models::Category> =
+        table.values().flat_map(|prices| prices.keys()).collect();
+
+    sheet.write_string_with_format(0, 0, "カテゴリー", &formats.header)?;
+    for (col, month) in (1..).zip(&months) {
+        sheet.write_date_with_format(0, col, *month, &formats.month_header)?;
+    }
+    let total_col = months.len() as u16 + 1;
+    sheet.write_string_with_format(0, total_col, "合計", &formats.header)?;
+    for (row, category) in (1..).zip(&categories) {
+        let kind = match category {
+            models::Category::Income(_) => "収入",
+            models::Category::Expense(_) => "支出",
+        };
+        sheet.write_string(row, 0, format!("{}:{}", kind, category.get_label()))?;
+        for (col, month) in (1..).zip(&months) {
+            let price = table[*month].get(*category).copied().unwrap_or(0);
+            sheet.write_number_with_format(row, col, price, &formats.money)?;
+        }
+        let formula = format!(
+            "=SUM({}:{})",
+            get_cell_name(row, 1),
+            get_cell_name(row, total_col - 1)
+        );
+        sheet.write_formula_with_format(row, total_col, Formula::new(formula), &formats.total)?;
+    }
+    sheet.set_freeze_panes(1, 1)?;
+    sheet.autofit();
+    Ok(())
+}
+
+fn write_total_row(
+    sheet: &mut Worksheet,
+    row: u32,
+    cols: std::ops::RangeInclusive<u16>,
+    formats: &Formats,
+) -> Result<(), XlsxError> {
+    sheet.write_string_with_format(row, 0, "合計", &formats.header)?;
+    for col in cols {
+        let formula = format!(
+            "=SUM({}:{})",
+            get_cell_name(1, col),
+            get_cell_name(row - 1, col)
+        );
+        sheet.write_formula_with_format(row, col, Formula::new(formula), &formats.total)?;
+    }
+    Ok(())
+}
+
+fn get_cell_name(row: u32, col: u16) -> String {
+    rust_xlsxwriter::utility::row_col_to_cell(row, col)
+}
+
+// 設定の通貨表記に合わせたExcelの表示形式
+fn get_number_format(currency: &CurrencyFormat) -> String {
+    let digits = if currency.thousands_separator {
+        "#,##0"
+    } else {
+        "0"
+    };
+    let positive = match currency.symbol_position {
+        SymbolPosition::Prefix => format!("\"{}\"{}", currency.symbol, digits),
+        SymbolPosition::Suffix => format!("{}\"{}\"", digits, currency.symbol),
+    };
+    format!("{};-{}", positive, positive)
+}
+
+#[cfg(test)]
+mod xlsx_test {
+    use super::*;
+
+    #[test]
+    fn test_get_number_format() {
+        assert_eq!(
+            get_number_format(&CurrencyFormat::default()),
+            "0\"円\";-0\"円\""
+        );
+        let yen = CurrencyFormat {
+            symbol: "¥".to_string(),
+            symbol_position: SymbolPosition::Prefix,
+            thousands_separator: true,
+        };
+        assert_eq!(get_number_format(&yen), "\"¥\"#,##0;-\"¥\"#,##0");
+    }
+
+    #[test]
+    fn test_write() {
+        let data = vec![
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                800,
+                NaiveDate::from_ymd_opt(2025, 8, 1).unwrap(),
+            )
+            .with_id(1),
+        ];
+        let bytes = write(&data, &Config::default());
+        assert_eq!(&bytes[..2], b"PK");
+        assert!(!write(&[], &Config::default()).is_empty());
+    }
+}
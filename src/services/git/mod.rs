@@ -0,0 +1,260 @@
+use crate::config::{Config, CurrencyFormat};
+use crate::{models, services};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+const DEFAULT_AUTHOR_NAME: &str = "kakeibo";
+const DEFAULT_AUTHOR_EMAIL: &str = "kakeibo@localhost";
+
+pub fn run_history(config: &Config) {
+    let dir = get_repository_dir(&config.ledger_path);
+    if !dir.join(".git").exists() {
+        panic!("家計簿ディレクトリがgitリポジトリではありません");
+    }
+    let output = run_git(&dir, &["log", "--date=short", "--format=%h %ad %s"]);
+    print!("{}", String::from_utf8_lossy(&output.stdout));
+}
+
+// 書き込み前後の差分からコミットメッセージを作る
+pub fn build_message(
+    before: &[models::Item],
+    after: &[models::Item],
+    currency: &CurrencyFormat,
+) -> String {
+    let (added, removed) = services::io::diff_items(before, after);
+    match (added.as_slice(), removed.as_slice()) {
+        ([item], []) => format!("Add: {}", format_item(item, currency)),
+        ([], [item]) => format!("Delete: {}", format_item(item, currency)),
+        // IDが同じ場合だけ同じ項目の編集とみなす
+        ([item], [old]) if item.get_id() == old.get_id() => {
+            format!("Update: {}", format_item(item, currency))
+        }
+        ([item], [old]) => format!(
+            "Add: {}; Delete: {}",
+            format_item(item, currency),
+            format_item(old, currency)
+        ),
+        _ => format!("Update: {} added, {} removed", added.len(), removed.len()),
+    }
+}
+
+// 家計簿と付随ファイル・アーカイブの変更をまとめてコミットする。初回はリポジトリを作成する。
+// 家計簿と同じディレクトリにあるほかのファイルはコミットしない
+pub fn commit(ledger_path: &str, message: &str) {
+    let dir = get_repository_dir(ledger_path);
+    if !dir.join(".git").exists() {
+        init_repository(&dir);
+    }
+    let files: Vec<String> = get_tracked_files(ledger_path)
+        .into_iter()
+        .filter(|file| dir.join(file).exists())
+        .collect();
+    let mut args = vec!["add", "-A", "--"];
+    args.extend(files.iter().map(|file| file.as_str()));
+    run_git(&dir, &args);
+    if !has_staged_changes(&dir) {
+        return;
+    }
+    let mut args = Vec::new();
+    if !has_identity(&dir) {
+        args.extend([
+            "-c".to_string(),
+            format!("user.name={}", DEFAULT_AUTHOR_NAME),
+            "-c".to_string(),
+            format!("user.email={}", DEFAULT_AUTHOR_EMAIL),
+        ]);
+    }
+    args.extend([
+        "commit".to_string(),
+        "--quiet".to_string(),
+        "-m".to_string(),
+        message.to_string(),
+    ]);
+    let args: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+    run_git(&dir, &args);
+}
+
+fn get_tracked_files(ledger_path: &str) -> Vec<String> {
+    let ledger_file = Path::new(ledger_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .expect("ファイル名が不正です");
+    let mut files: Vec<String> = [
+        ledger_file,
+        ".gitignore",
+        services::account::ACCOUNT_FILE,
+        services::archive::INDEX_FILE,
+        services::budget::BUDGET_FILE,
+        services::envelope::ENVELOPE_FILE,
+        services::import::HISTORY_FILE,
+        services::reconcile::RECONCILIATION_FILE,
+        services::recurring::RECURRING_FILE,
+        services::transfer::TRANSFER_FILE,
+    ]
+    .iter()
+    .map(|file| file.to_string())
+    .collect();
+    files.extend(
+        services::archive::read_index(ledger_path)
+            .iter()
+            .map(|summary| summary.get_file().to_string()),
+    );
+    files
+}
+
+fn has_staged_changes(dir: &Path) -> bool {
+    let status = Command::new("git")
+        .args(["diff", "--cached", "--quiet"])
+        .current_dir(dir)
+        .status()
+        .expect("gitコマンドの実行に失敗しました");
+    !status.success()
+}
+
+fn get_repository_dir(file_path: &str) -> PathBuf {
+    let dir = Path::new(file_path).parent().unwrap_or(Path::new(""));
+    if dir.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        dir.to_path_buf()
+    }
+}
+
+fn init_repository(dir: &Path) {
+    run_git(dir, &["init", "--quiet"]);
+    fs::write(dir.join(".gitignore"), "backups/\n").expect(".gitignoreの作成に失敗しました");
+}
+
+fn has_identity(dir: &Path) -> bool {
+    let output = Command::new("git")
+        .args(["config", "user.email"])
+        .current_dir(dir)
+        .output()
+        .expect("gitコマンドの実行に失敗しました");
+    output.status.success() && !output.stdout.is_empty()
+}
+
+fn run_git(dir: &Path, args: &[&str]) -> Output {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("gitコマンドの実行に失敗しました");
+    if !output.status.success() {
+        panic!(
+            "gitコマンドの実行に失敗しました: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    output
+}
+
+fn format_item(item: &models::Item, currency: &CurrencyFormat) -> String {
+    format!(
+        "{} {} {}",
+        item.get_name(),
+        currency.format(item.get_price() as i64),
+        item.get_date()
+    )
+}
+
+#[cfg(test)]
+mod git_test {
+    use super::*;
+    use crate::config::SymbolPosition;
+    use chrono::NaiveDate;
+
+    fn get_test_item(name: &str, price: u32) -> models::Item {
+        models::Item::new(
+            name.to_string(),
+            models::Category::Expense(models::ExpenseCategory::Food),
+            price,
+            NaiveDate::from_ymd_opt(2025, 8, 1).unwrap(),
+        )
+    }
+
+    fn get_yen() -> CurrencyFormat {
+        CurrencyFormat {
+            symbol: "¥".to_string(),
+            symbol_position: SymbolPosition::Prefix,
+            thousands_separator: true,
+        }
+    }
+
+    #[test]
+    fn test_build_message() {
+        let lunch = get_test_item("ランチ", 800);
+        let dinner = get_test_item("ディナー", 3000);
+
+        let result = build_message(&[], &[get_test_item("ランチ", 800)], &get_yen());
+        assert_eq!(result, "Add: ランチ ¥800 2025-08-01");
+
+        let result = build_message(&[get_test_item("ランチ", 800)], &[], &get_yen());
+        assert_eq!(result, "Delete: ランチ ¥800 2025-08-01");
+
+        let result = build_message(
+            &[get_test_item("ディナー", 300)],
+            std::slice::from_ref(&dinner),
+            &get_yen(),
+        );
+        assert_eq!(result, "Update: ディナー ¥3,000 2025-08-01");
+
+        // IDが異なる場合は追加と削除
+        let result = build_message(
+            &[get_test_item("ランチ", 800)],
+            &[dinner.with_id(2)],
+            &get_yen(),
+        );
+        assert_eq!(
+            result,
+            "Add: ディナー ¥3,000 2025-08-01; Delete: ランチ ¥800 2025-08-01"
+        );
+
+        let result = build_message(&[lunch], &[], &CurrencyFormat::default());
+        assert_eq!(result, "Delete: ランチ 800円 2025-08-01");
+
+        let result = build_message(&[], &[], &get_yen());
+        assert_eq!(result, "Update: 0 added, 0 removed");
+    }
+
+    #[test]
+    fn test_commit() {
+        let test_dir = std::env::temp_dir().join("kakeibo_git_test");
+        let _ = fs::remove_dir_all(&test_dir);
+        fs::create_dir_all(&test_dir).unwrap();
+        let mut config = Config {
+            ledger_path: test_dir.join("data.json").to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+        config.git.enabled = true;
+
+        // 家計簿と関係のないファイルはコミットしない
+        fs::write(test_dir.join("memo.txt"), "メモ").unwrap();
+        let mut data = vec![get_test_item("ランチ", 800)];
+        services::io::write_to_json(&data, &config.ledger_path, &config);
+        data.push(get_test_item("ディナー", 3000));
+        services::io::write_to_json(&data, &config.ledger_path, &config);
+        // 変更がない場合はコミットしない
+        services::io::write_to_json(&data, &config.ledger_path, &config);
+        services::budget::write_budgets(
+            &std::collections::BTreeMap::from([(models::ExpenseCategory::Food, 50000)]),
+            &config,
+            "Set budget: 食費 50000円",
+        );
+
+        let output = run_git(&test_dir, &["log", "--format=%s"]);
+        let log = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(
+            log,
+            "Set budget: 食費 50000円\nAdd: ディナー 3000円 2025-08-01\nAdd: ランチ 800円 2025-08-01\n"
+        );
+        let output = run_git(&test_dir, &["ls-files"]);
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            ".gitignore\nbudgets.json\ndata.json\n"
+        );
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+}
@@ -0,0 +1,613 @@
+use super::{ImportOptions, ImportResult, RowError};
+use crate::config::Config;
+use crate::models;
+use crate::services::io::TextEncoding;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+
+// CSVのどの列を日付・金額・品目名・カテゴリーとして読むかの定義
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CsvMapping {
+    pub description: Option<String>,
+    #[serde(default)]
+    pub encoding: TextEncoding,
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    #[serde(default = "default_has_header")]
+    pub has_header: bool,
+    #[serde(default)]
+    pub skip_rows: usize,
+    #[serde(default = "default_date_format")]
+    pub date_format: String,
+    #[serde(default)]
+    pub sign: SignConvention,
+    #[serde(default = "default_income_category")]
+    pub default_income_category: models::IncomeCategory,
+    #[serde(default = "default_expense_category")]
+    pub default_expense_category: models::ExpenseCategory,
+    pub columns: ColumnMapping,
+    #[serde(default)]
+    pub rules: Vec<CategoryRule>,
+    #[serde(default)]
+    pub category_map: BTreeMap<String, String>,
+    #[serde(default)]
+    pub skip: Vec<SkipRule>,
+}
+
+// 品目名に指定の文字列を含む場合に使うカテゴリー。収入・支出の区別は金額の符号で決まる
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CategoryRule {
+    pub contains: String,
+    pub category: String,
+}
+
+// 指定の列が指定の値の行は取り込まない(振替や集計対象外の行など)
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SkipRule {
+    pub column: ColumnRef,
+    pub equals: String,
+}
+
+// 金額列が1つの場合の符号の解釈
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SignConvention {
+    #[default]
+    ExpensePositive,
+    IncomePositive,
+}
+
+// 金額は amount の1列、または income と expense の2列で指定する
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnMapping {
+    pub date: ColumnRef,
+    pub name: ColumnRef,
+    pub amount: Option<ColumnRef>,
+    pub income: Option<ColumnRef>,
+    pub expense: Option<ColumnRef>,
+    pub category: Option<ColumnRef>,
+    pub subcategory: Option<ColumnRef>,
+    pub fallback_name: Option<ColumnRef>,
+}
+
+// 列はヘッダー名または0始まりの列番号で指定する
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum ColumnRef {
+    Index(usize),
+    Name(String),
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+fn default_has_header() -> bool {
+    true
+}
+
+fn default_date_format() -> String {
+    "%Y-%m-%d".to_string()
+}
+
+fn default_income_category() -> models::IncomeCategory {
+    models::IncomeCategory::Other
+}
+
+fn default_expense_category() -> models::ExpenseCategory {
+    models::ExpenseCategory::Other
+}
+
+pub fn run(config: &Config, file_path: &str, mapping: &CsvMapping, options: ImportOptions) {
+    println!("CSVファイルを取り込みます");
+    super::run_file(config, file_path, options, |bytes| parse(bytes, mapping));
+}
+
+impl CsvMapping {
+    pub fn from_file(path: &str) -> Self {
+        let content = fs::read_to_string(path).expect("マッピングファイルの読み込みに失敗しました");
+        Self::from_toml(&content)
+    }
+
+    pub fn from_toml(content: &str) -> Self {
+        let mapping: CsvMapping = toml::from_str(content)
+            .unwrap_or_else(|e| panic!("マッピングファイルの形式が不正です: {}", e));
+        if !mapping.delimiter.is_ascii() {
+            panic!("区切り文字は半角文字で指定してください");
+        }
+        let columns = &mapping.columns;
+        if columns.amount.is_none() && columns.income.is_none() && columns.expense.is_none() {
+            panic!("金額の列(amount または income/expense)を指定してください");
+        }
+        mapping
+    }
+}
+
+pub fn parse(bytes: &[u8], mapping: &CsvMapping) -> ImportResult {
+    let content = mapping.encoding.decode(bytes);
+    let mut delimiter = [0; 4];
+    mapping.delimiter.encode_utf8(&mut delimiter);
+    let mut reader = ::csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter[0])
+        .from_reader(content.as_bytes());
+    let mut records = reader.records().skip(mapping.skip_rows);
+
+    let headers: HashMap<String, usize> = if mapping.has_header {
+        let header = records
+            .next()
+            .and_then(|record| record.ok())
+            .expect("ヘッダー行が存在しません");
+        header
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (name.trim().to_string(), index))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+    let columns = ResolvedColumns::resolve(mapping, &headers);
+
+    // 空行や改行を含む列があってもファイル上の行番号を報告できるよう、読み込んだ位置の行を使う
+    let mut result = ImportResult::default();
+    for record in records {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let row = e.position().map_or(0, |position| position.line() as usize);
+                result.errors.push(RowError::new(
+                    row,
+                    format!("CSVとして読み込めません: {}", e),
+                ));
+                continue;
+            }
+        };
+        let row = record
+            .position()
+            .map_or(0, |position| position.line() as usize);
+        let fields: Vec<&str> = record.iter().collect();
+        if fields.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        match parse_row(&fields, &columns, mapping) {
+            Ok(Some((item, unmatched))) => {
+                if let Some(source) = unmatched {
+                    *result.unmatched_categories.entry(source).or_insert(0) += 1;
+                }
+                result.items.push(item);
+            }
+            Ok(None) => {}
+            Err(message) => result.errors.push(RowError::new(row, message)),
+        }
+    }
+    result
+}
+
+struct ResolvedColumns {
+    date: usize,
+    name: usize,
+    amount: Option<usize>,
+    income: Option<usize>,
+    expense: Option<usize>,
+    category: Option<usize>,
+    subcategory: Option<usize>,
+    fallback_name: Option<usize>,
+    skip: Vec<(usize, String)>,
+}
+
+impl ResolvedColumns {
+    fn resolve(mapping: &CsvMapping, headers: &HashMap<String, usize>) -> Self {
+        let columns = &mapping.columns;
+        let resolve = |column: &ColumnRef| match column {
+            ColumnRef::Index(index) => *index,
+            ColumnRef::Name(name) => *headers
+                .get(name)
+                .unwrap_or_else(|| panic!("列が見つかりません: {}", name)),
+        };
+        ResolvedColumns {
+            date: resolve(&columns.date),
+            name: resolve(&columns.name),
+            amount: columns.amount.as_ref().map(resolve),
+            income: columns.income.as_ref().map(resolve),
+            expense: columns.expense.as_ref().map(resolve),
+            category: columns.category.as_ref().map(resolve),
+            subcategory: columns.subcategory.as_ref().map(resolve),
+            fallback_name: columns.fallback_name.as_ref().map(resolve),
+            skip: mapping
+                .skip
+                .iter()
+                .map(|rule| (resolve(&rule.column), rule.equals.clone()))
+                .collect(),
+        }
+    }
+}
+
+// 金額が空欄または0の行と除外条件に当たる行は読み飛ばす。
+// カテゴリーを決められなかった場合は元のカテゴリー名も返す
+fn parse_row(
+    fields: &[&str],
+    columns: &ResolvedColumns,
+    mapping: &CsvMapping,
+) -> Result<Option<(models::Item, Option<String>)>, String> {
+    let get = |index: usize| fields.get(index).map(|field| field.trim()).unwrap_or("");
+    if columns
+        .skip
+        .iter()
+        .any(|(index, value)| get(*index) == value)
+    {
+        return Ok(None);
+    }
+
+    let date_field = get(columns.date);
+    let date = NaiveDate::parse_from_str(date_field, &mapping.date_format)
+        .map_err(|_| format!("日付を解釈できません: {}", date_field))?;
+
+    let amount = match columns.amount {
+        Some(index) => parse_amount(get(index))?.map(|amount| match mapping.sign {
+            SignConvention::ExpensePositive => -amount,
+            SignConvention::IncomePositive => amount,
+        }),
+        None => {
+            let income = columns
+                .income
+                .map(|index| parse_amount(get(index)))
+                .transpose()?;
+            let expense = columns
+                .expense
+                .map(|index| parse_amount(get(index)))
+                .transpose()?;
+            match (income.flatten(), expense.flatten()) {
+                (None, None) => None,
+                (income, expense) => Some(income.unwrap_or(0) - expense.unwrap_or(0)),
+            }
+        }
+    };
+    let amount = match amount {
+        Some(amount) if amount != 0 => amount,
+        _ => return Ok(None),
+    };
+    let price = u32::try_from(amount.unsigned_abs())
+        .map_err(|_| format!("金額が大きすぎます: {}", amount))?;
+
+    let name = match get(columns.name) {
+        "" => columns.fallback_name.map(get).unwrap_or(""),
+        name => name,
+    };
+    if name.is_empty() {
+        return Err("品目名が空です".to_string());
+    }
+    let category_name = columns.category.map(get).unwrap_or("");
+    let subcategory_name = columns.subcategory.map(get).unwrap_or("");
+    let is_income = amount > 0;
+    let (category, unmatched) =
+        match find_category(category_name, subcategory_name, name, is_income, mapping) {
+            Some(category) => (category, None),
+            None => (
+                get_default_category(is_income, mapping),
+                get_source_category(category_name, subcategory_name),
+            ),
+        };
+    Ok(Some((
+        models::Item::new(name.to_string(), category, price, date),
+        unmatched,
+    )))
+}
+
+// カテゴリーの対応表(大項目/中項目、大項目の順)、カテゴリー列、品目名のルールの順に決める
+fn find_category(
+    category_name: &str,
+    subcategory_name: &str,
+    name: &str,
+    is_income: bool,
+    mapping: &CsvMapping,
+) -> Option<models::Category> {
+    let mapped_names = [
+        format!("{}/{}", category_name, subcategory_name),
+        category_name.to_string(),
+    ];
+    let mapped_names = mapped_names
+        .iter()
+        .filter_map(|key| mapping.category_map.get(key))
+        .map(|category| category.as_str());
+    let rule_names = mapping
+        .rules
+        .iter()
+        .filter(|rule| name.contains(&rule.contains))
+        .map(|rule| rule.category.as_str());
+    let mut candidates = mapped_names
+        .chain(std::iter::once(category_name))
+        .chain(rule_names);
+    if is_income {
+        candidates
+            .find_map(models::IncomeCategory::from_name)
+            .map(models::Category::Income)
+    } else {
+        candidates
+            .find_map(models::ExpenseCategory::from_name)
+            .map(models::Category::Expense)
+    }
+}
+
+fn get_default_category(is_income: bool, mapping: &CsvMapping) -> models::Category {
+    if is_income {
+        models::Category::Income(mapping.default_income_category)
+    } else {
+        models::Category::Expense(mapping.default_expense_category)
+    }
+}
+
+// 報告用の元のカテゴリー名。カテゴリー列がない場合は報告しない
+fn get_source_category(category_name: &str, subcategory_name: &str) -> Option<String> {
+    match (category_name, subcategory_name) {
+        ("", _) => None,
+        (category, "") => Some(category.to_string()),
+        (category, subcategory) => Some(format!("{}/{}", category, subcategory)),
+    }
+}
+
+// 桁区切りや通貨記号を取り除き、△/▲は負数として読む
+pub fn parse_amount(field: &str) -> Result<Option<i64>, String> {
+    let cleaned: String = field
+        .chars()
+        .filter(|c| !matches!(c, ',' | '¥' | '￥' | '円' | ' ' | '\u{3000}'))
+        .collect();
+    if cleaned.is_empty() {
+        return Ok(None);
+    }
+    let (negative, digits) = match cleaned.strip_prefix(['△', '▲', '-']) {
+        Some(digits) => (true, digits),
+        None => (false, cleaned.as_str()),
+    };
+    let amount: i64 = digits
+        .parse()
+        .map_err(|_| format!("金額を解釈できません: {}", field))?;
+    Ok(Some(if negative { -amount } else { amount }))
+}
+
+#[cfg(test)]
+mod csv_test {
+    use super::*;
+
+    fn get_card_mapping() -> CsvMapping {
+        CsvMapping::from_toml(
+            r#"
+            date_format = "%Y/%m/%d"
+
+            [columns]
+            date = "利用日"
+            name = "利用店名"
+            amount = "利用金額"
+            "#,
+        )
+    }
+
+    #[test]
+    fn test_from_toml() {
+        let mapping = get_card_mapping();
+        assert_eq!(mapping.encoding, TextEncoding::Utf8);
+        assert_eq!(mapping.delimiter, ',');
+        assert!(mapping.has_header);
+        assert_eq!(mapping.sign, SignConvention::ExpensePositive);
+        assert_eq!(mapping.columns.date, ColumnRef::Name("利用日".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "金額の列(amount または income/expense)を指定してください")]
+    fn test_from_toml_without_amount() {
+        CsvMapping::from_toml("[columns]\ndate = 0\nname = 1");
+    }
+
+    #[test]
+    fn test_parse_card_statement() {
+        let csv = "利用日,利用店名,利用金額\n2025/08/01,ランチ,\"1,200\"\n2025/08/03,返品,-500\n\n";
+        let ImportResult { items, errors, .. } = parse(csv.as_bytes(), &get_card_mapping());
+
+        assert!(errors.is_empty());
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].get_name(), "ランチ");
+        assert_eq!(items[0].get_price_for_summary(), -1200);
+        assert_eq!(
+            items[0].get_date(),
+            NaiveDate::from_ymd_opt(2025, 8, 1).unwrap()
+        );
+        assert_eq!(
+            *items[1].get_item_category(),
+            models::Category::Income(models::IncomeCategory::Other)
+        );
+    }
+
+    #[test]
+    fn test_parse_shift_jis_bank_statement() {
+        let mapping = CsvMapping::from_toml(
+            r#"
+            encoding = "shift_jis"
+            date_format = "%Y年%m月%d日"
+            default_expense_category = "Food"
+
+            [columns]
+            date = "日付"
+            name = "摘要"
+            income = "お預り金額"
+            expense = "お引出し金額"
+            "#,
+        );
+        let csv = "日付,摘要,お預り金額,お引出し金額\n2025年8月25日,給与,300000,\n2025年8月27日,スーパー,,4500\n";
+        let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode(csv);
+        let ImportResult { items, errors, .. } = parse(&bytes, &mapping);
+
+        assert!(errors.is_empty());
+        assert_eq!(items[0].get_price_for_summary(), 300000);
+        assert_eq!(items[1].get_name(), "スーパー");
+        assert_eq!(
+            *items[1].get_item_category(),
+            models::Category::Expense(models::ExpenseCategory::Food)
+        );
+    }
+
+    #[test]
+    fn test_parse_without_header() {
+        let mapping = CsvMapping::from_toml(
+            r#"
+            has_header = false
+            skip_rows = 1
+            delimiter = "	"
+            sign = "income_positive"
+
+            [columns]
+            date = 0
+            name = 1
+            amount = 2
+            category = 3
+            "#,
+        );
+        let tsv = "明細\n2025-08-01\tランチ\t-800\t食費\n2025-08-25\t給料\t300000\t給与\n";
+        let ImportResult { items, errors, .. } = parse(tsv.as_bytes(), &mapping);
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            *items[0].get_item_category(),
+            models::Category::Expense(models::ExpenseCategory::Food)
+        );
+        assert_eq!(
+            *items[1].get_item_category(),
+            models::Category::Income(models::IncomeCategory::Salary)
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let csv =
+            "利用日,利用店名,利用金額\n08/01,ランチ,800\n2025/08/02,ランチ,八百\n2025/08/03,,800\n";
+        let ImportResult { items, errors, .. } = parse(csv.as_bytes(), &get_card_mapping());
+
+        assert!(items.is_empty());
+        assert_eq!(
+            errors,
+            vec![
+                RowError::new(2, "日付を解釈できません: 08/01".to_string()),
+                RowError::new(3, "金額を解釈できません: 八百".to_string()),
+                RowError::new(4, "品目名が空です".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_line_number() {
+        // 空行と改行を含む列の分だけ行番号がずれないこと
+        let csv = "利用日,利用店名,利用金額\n\n2025/08/01,\"ランチ\n(会社近く)\",800\n2025/08/02,ランチ,八百\n";
+        let ImportResult { items, errors, .. } = parse(csv.as_bytes(), &get_card_mapping());
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(
+            errors,
+            vec![RowError::new(5, "金額を解釈できません: 八百".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_get_category_rules() {
+        let mapping = CsvMapping::from_toml(
+            r#"
+            [columns]
+            date = 0
+            name = 1
+            amount = 2
+
+            [[rules]]
+            contains = "ランチ"
+            category = "食費"
+
+            [[rules]]
+            contains = "賞与"
+            category = "Bonus"
+            "#,
+        );
+        assert_eq!(
+            find_category("", "", "ランチ 新宿店", false, &mapping),
+            Some(models::Category::Expense(models::ExpenseCategory::Food))
+        );
+        assert_eq!(
+            find_category("Hobby", "", "ランチ", false, &mapping),
+            Some(models::Category::Expense(models::ExpenseCategory::Hobby))
+        );
+        assert_eq!(
+            find_category("", "", "夏季賞与", true, &mapping),
+            Some(models::Category::Income(models::IncomeCategory::Bonus))
+        );
+        // 収入に支出のカテゴリーは使わない
+        assert_eq!(find_category("", "", "ランチ代返金", true, &mapping), None);
+    }
+
+    #[test]
+    fn test_parse_category_map() {
+        let mapping = CsvMapping::from_toml(
+            r#"
+            [columns]
+            date = "日付"
+            name = "品目"
+            fallback_name = "中項目"
+            amount = "金額"
+            category = "大項目"
+            subcategory = "中項目"
+
+            [[skip]]
+            column = "振替"
+            equals = "1"
+
+            [category_map]
+            "食費" = "Food"
+            "食費/外食" = "Hobby"
+            "#,
+        );
+        let csv = "日付,品目,金額,大項目,中項目,振替
+                   2025-08-01,スーパー,3000,食費,食料品,0
+                   2025-08-02,,5000,食費,外食,0
+                   2025-08-03,口座へ移動,10000,,,1
+                   2025-08-04,飲み会,4000,交際費,飲み会,0
+                   2025-08-05,プレゼント,2000,交際費,プレゼント,0
+                   2025-08-06,お祝い,1000,交際費,,0
+";
+        let result = parse(csv.as_bytes(), &mapping);
+
+        assert!(result.errors.is_empty());
+        assert_eq!(result.items.len(), 5);
+        assert_eq!(
+            *result.items[0].get_item_category(),
+            models::Category::Expense(models::ExpenseCategory::Food)
+        );
+        assert_eq!(result.items[1].get_name(), "外食");
+        assert_eq!(
+            *result.items[1].get_item_category(),
+            models::Category::Expense(models::ExpenseCategory::Hobby)
+        );
+        assert_eq!(
+            *result.items[2].get_item_category(),
+            models::Category::Expense(models::ExpenseCategory::Other)
+        );
+        assert_eq!(
+            result.unmatched_categories,
+            BTreeMap::from([
+                ("交際費".to_string(), 1),
+                ("交際費/プレゼント".to_string(), 1),
+                ("交際費/飲み会".to_string(), 1),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("1,234"), Ok(Some(1234)));
+        assert_eq!(parse_amount("¥800"), Ok(Some(800)));
+        assert_eq!(parse_amount("△500"), Ok(Some(-500)));
+        assert_eq!(parse_amount("-500円"), Ok(Some(-500)));
+        assert_eq!(parse_amount(""), Ok(None));
+        assert!(parse_amount("abc").is_err());
+    }
+}
@@ -0,0 +1,354 @@
+use super::{ImportOptions, ImportResult, RowError};
+use crate::config::Config;
+use crate::models;
+use crate::services::io::TextEncoding;
+use chrono::NaiveDate;
+
+pub fn run(config: &Config, file_path: &str, options: ImportOptions) {
+    println!("仕訳帳ファイルを取り込みます");
+    super::run_file(config, file_path, options, parse);
+}
+
+// beancount・hledger(ledger)の単純な仕訳帳を読む。
+// Income:/Expenses: の勘定への記帳をそれぞれ1件の項目にし、それ以外(振替など)は読み飛ばす。
+// 1つの取引に同じ収支の記帳が複数あれば、内訳を持つ1件の項目にまとめる。
+// カテゴリーは Expenses:Food:外食 の2階層目で判定する
+pub fn parse(bytes: &[u8]) -> ImportResult {
+    let content = TextEncoding::detect(bytes).decode(bytes);
+    let mut result = ImportResult::default();
+    let mut transaction: Option<Transaction> = None;
+    for (row, line) in (1..).zip(content.lines()) {
+        let is_posting = line.starts_with([' ', '\t']);
+        let line = strip_comment(line).trim();
+        if is_posting {
+            if let Some(transaction) = transaction.as_mut().filter(|_| !line.is_empty()) {
+                transaction.postings.push(parse_posting(line));
+            }
+            continue;
+        }
+        if let Some(transaction) = transaction.take() {
+            push_transaction(&mut result, transaction);
+        }
+        if line.starts_with(|c: char| c.is_ascii_digit()) {
+            transaction = match parse_header(line) {
+                Ok(Some((date, name))) => Some(Transaction {
+                    row,
+                    date,
+                    name,
+                    postings: Vec::new(),
+                }),
+                Ok(None) => None,
+                Err(message) => {
+                    result.errors.push(RowError::new(row, message));
+                    None
+                }
+            };
+        }
+    }
+    if let Some(transaction) = transaction {
+        push_transaction(&mut result, transaction);
+    }
+    result
+}
+
+struct Transaction {
+    row: usize,
+    date: NaiveDate,
+    name: String,
+    postings: Vec<(String, Option<Result<i64, String>>)>,
+}
+
+fn push_transaction(result: &mut ImportResult, transaction: Transaction) {
+    match get_items(&transaction) {
+        Ok(items) => result.items.extend(items),
+        Err(message) => result.errors.push(RowError::new(transaction.row, message)),
+    }
+}
+
+// 金額が省略された記帳は、ほかの記帳の合計と釣り合う金額とみなす
+fn get_items(transaction: &Transaction) -> Result<Vec<models::Item>, String> {
+    let mut amounts = Vec::new();
+    for (_, amount) in &transaction.postings {
+        amounts.push(amount.clone().transpose()?);
+    }
+    let balance: i64 = amounts.iter().flatten().sum();
+    let mut items = Vec::new();
+    for ((account, _), amount) in transaction.postings.iter().zip(amounts) {
+        let amount = amount.unwrap_or(-balance);
+        let category = match account.split_once(':') {
+            Some((root, category))
+                if root.eq_ignore_ascii_case("income") || root.eq_ignore_ascii_case("expenses") =>
+            {
+                category.split(':').next()
+            }
+            _ => continue,
+        };
+        if amount == 0 {
+            continue;
+        }
+        // 記帳の符号は家計簿と逆(支出が正、収入が負)
+        let item = super::new_item(&transaction.name, -amount, transaction.date, category)?;
+        items.push(item);
+    }
+    Ok(merge_splits(items))
+}
+
+// 収入と支出が混ざる取引は内訳にできないため、項目を分けたままにする
+fn merge_splits(items: Vec<models::Item>) -> Vec<models::Item> {
+    let is_income =
+        |item: &models::Item| matches!(item.get_item_category(), models::Category::Income(_));
+    if items.len() < 2
+        || items
+            .iter()
+            .any(|item| is_income(item) != is_income(&items[0]))
+    {
+        return items;
+    }
+    let price = items.iter().map(|item| item.get_price()).sum();
+    let splits = items
+        .iter()
+        .map(|item| models::Split::new(*item.get_item_category(), item.get_price()))
+        .collect();
+    let first = &items[0];
+    vec![
+        models::Item::new(
+            first.get_name().to_string(),
+            *first.get_item_category(),
+            price,
+            first.get_date(),
+        )
+        .with_splits(splits),
+    ]
+}
+
+// "2025-08-01 * \"店\" \"品目\"" や "2025/08/01 品目" を読む。open などの宣言はNoneを返す
+fn parse_header(line: &str) -> Result<Option<(NaiveDate, String)>, String> {
+    let (date_field, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let date = ["%Y-%m-%d", "%Y/%m/%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(date_field, format).ok())
+        .ok_or_else(|| format!("日付を解釈できません: {}", date_field))?;
+    let rest = rest.trim();
+    let flag = rest
+        .strip_prefix(['*', '!'])
+        .or_else(|| rest.strip_prefix("txn "));
+    let rest = match flag {
+        Some(rest) => rest.trim(),
+        None if rest.starts_with('"') => rest,
+        None if rest.split_whitespace().next().is_some_and(is_directive) => return Ok(None),
+        None => rest,
+    };
+    // beancountの "支払先" "摘要" は摘要を品目名にする
+    let name = if rest.starts_with('"') {
+        rest.split('"')
+            .rfind(|part| !part.trim().is_empty())
+            .unwrap_or("")
+            .to_string()
+    } else {
+        rest.to_string()
+    };
+    if name.is_empty() {
+        return Err("品目名が空です".to_string());
+    }
+    Ok(Some((date, name)))
+}
+
+fn is_directive(word: &str) -> bool {
+    matches!(
+        word,
+        "open"
+            | "close"
+            | "balance"
+            | "pad"
+            | "note"
+            | "document"
+            | "price"
+            | "event"
+            | "commodity"
+    )
+}
+
+// "Expenses:Food  800 JPY" を勘定と金額に分ける。メタデータ(key: value)は読み飛ばす
+fn parse_posting(line: &str) -> (String, Option<Result<i64, String>>) {
+    let (account, amount) = line
+        .split_once(|c: char| c.is_whitespace())
+        .unwrap_or((line, ""));
+    if account.ends_with(':') {
+        return (String::new(), None);
+    }
+    let amount = amount.trim();
+    let amount = (!amount.is_empty()).then(|| parse_amount(amount));
+    (account.to_string(), amount)
+}
+
+// "800 JPY" "JPY 800" "¥1,500" "-800" を読む。円以外の通貨や価格(@)・取得原価({})の付いた金額は取り込めない
+fn parse_amount(amount: &str) -> Result<i64, String> {
+    let words: Vec<&str> = amount.split_whitespace().collect();
+    let foreign = words
+        .iter()
+        .find(|word| **word != "JPY" && word.chars().all(|c| c.is_ascii_uppercase()));
+    if let Some(commodity) = foreign {
+        return Err(format!("円以外の通貨は取り込めません: {}", commodity));
+    }
+    let number = match words.as_slice() {
+        [number] | [number, "JPY"] | ["JPY", number] => *number,
+        _ => return Err(format!("金額を解釈できません: {}", amount)),
+    };
+    let (sign, number) = match number.strip_prefix('-') {
+        Some(number) => (-1, number),
+        None => (1, number),
+    };
+    let digits = number.strip_prefix(['¥', '￥']).unwrap_or(number);
+    if !digits.starts_with(|c: char| c.is_ascii_digit())
+        || !digits
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, ',' | '.'))
+    {
+        return Err(format!("金額を解釈できません: {}", amount));
+    }
+    super::parse_decimal_amount(digits).map(|amount| sign * amount)
+}
+
+fn strip_comment(line: &str) -> &str {
+    line.split(';').next().unwrap_or("")
+}
+
+#[cfg(test)]
+mod journal_test {
+    use super::*;
+    use crate::services::export::journal::{self, JournalSyntax};
+
+    #[test]
+    fn test_parse_beancount() {
+        let beancount = "option \"operating_currency\" \"JPY\"\n\n\
+                         2025-08-01 open Assets:Cash JPY\n\n\
+                         2025-08-01 * \"スーパー\" \"食材\"\n  id: \"1\"\n  Expenses:Food   3,000 JPY\n  Expenses:Hobby  500 JPY\n  Assets:Cash\n\n\
+                         2025-08-02 * \"ATM\"\n  Assets:Cash  10000 JPY\n  Assets:Bank  -10000 JPY\n\n\
+                         2025-08-25 * \"給料\"\n  Assets:Bank  300000 JPY\n  Income:Salary\n";
+        let result = parse(beancount.as_bytes());
+
+        assert!(result.errors.is_empty());
+        assert_eq!(result.items.len(), 2);
+        assert_eq!(result.items[0].get_name(), "食材");
+        assert_eq!(result.items[0].get_price_for_summary(), -3500);
+        assert_eq!(
+            result.items[0].get_category_prices(),
+            vec![
+                (
+                    models::Category::Expense(models::ExpenseCategory::Food),
+                    3000
+                ),
+                (
+                    models::Category::Expense(models::ExpenseCategory::Hobby),
+                    500
+                )
+            ]
+        );
+        assert_eq!(
+            *result.items[1].get_item_category(),
+            models::Category::Income(models::IncomeCategory::Salary)
+        );
+        assert_eq!(result.items[1].get_price_for_summary(), 300000);
+    }
+
+    #[test]
+    fn test_parse_hledger() {
+        let hledger = "; 家計簿\n2025/08/03 書店  ; id:3\n    expenses:本    ¥1,500\n    assets:cash\n\n\
+                       2025/13/01 不正な日付\n    Expenses:Food  1\n";
+        let result = parse(hledger.as_bytes());
+
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(result.items[0].get_name(), "書店");
+        assert_eq!(result.items[0].get_price_for_summary(), -1500);
+        assert_eq!(
+            result.errors,
+            vec![RowError::new(
+                6,
+                "日付を解釈できません: 2025/13/01".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("800 JPY"), Ok(800));
+        assert_eq!(parse_amount("JPY -800"), Ok(-800));
+        assert_eq!(parse_amount("¥1,500"), Ok(1500));
+        assert_eq!(parse_amount("-¥1,500"), Ok(-1500));
+        assert_eq!(parse_amount("3000"), Ok(3000));
+        assert_eq!(
+            parse_amount("10 USD @ 150 JPY"),
+            Err("円以外の通貨は取り込めません: USD".to_string())
+        );
+        assert_eq!(
+            parse_amount("$10"),
+            Err("金額を解釈できません: $10".to_string())
+        );
+        assert_eq!(
+            parse_amount("800 JPY {1 JPY}"),
+            Err("金額を解釈できません: 800 JPY {1 JPY}".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_foreign_currency() {
+        let beancount = "2025-08-01 * \"書店\"\n  Expenses:Book  10 USD @ 150 JPY\n  Assets:Cash\n";
+        let result = parse(beancount.as_bytes());
+
+        assert!(result.items.is_empty());
+        assert_eq!(
+            result.errors,
+            vec![RowError::new(
+                1,
+                "円以外の通貨は取り込めません: USD".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let data = vec![
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                800,
+                NaiveDate::from_ymd_opt(2025, 8, 26).unwrap(),
+            )
+            .with_id(1),
+            models::Item::new(
+                "賞与".to_string(),
+                models::Category::Income(models::IncomeCategory::Bonus),
+                500000,
+                NaiveDate::from_ymd_opt(2025, 12, 10).unwrap(),
+            )
+            .with_id(2),
+            models::Item::new(
+                "スーパー".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                3000,
+                NaiveDate::from_ymd_opt(2025, 12, 12).unwrap(),
+            )
+            .with_id(3)
+            .with_splits(vec![
+                models::Split::new(
+                    models::Category::Expense(models::ExpenseCategory::Food),
+                    2200,
+                ),
+                models::Split::new(
+                    models::Category::Expense(models::ExpenseCategory::Hobby),
+                    800,
+                ),
+            ]),
+        ];
+        for syntax in [JournalSyntax::Beancount, JournalSyntax::Hledger] {
+            let result = parse(journal::write(&data, syntax).as_bytes());
+            assert!(result.errors.is_empty());
+            assert_eq!(
+                result.items,
+                data.iter()
+                    .map(|item| item.clone().with_id(0))
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+}
@@ -0,0 +1,508 @@
+pub mod csv;
+pub mod journal;
+pub mod ofx;
+pub mod preset;
+pub mod qif;
+
+use crate::config::{Config, DuplicateAction};
+use crate::{models, services};
+use chrono::{Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const HISTORY_FILE: &str = "imports.json";
+
+// on_duplicateを指定しない場合は設定のduplicate.actionに従う。accountは取り込む項目すべての口座
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ImportOptions {
+    pub dry_run: bool,
+    pub assume_yes: bool,
+    pub on_duplicate: Option<DuplicateAction>,
+    pub account: Option<String>,
+}
+
+// 取り込み済みのファイル。内容のハッシュが同じファイルは再度取り込まない
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ImportRecord {
+    fingerprint: String,
+    file: String,
+    imported_at: NaiveDateTime,
+    item_count: usize,
+}
+
+// 取り込めなかった行。行番号はファイル上の1始まりの行
+#[derive(Debug, PartialEq)]
+pub struct RowError {
+    row: usize,
+    message: String,
+}
+
+impl RowError {
+    pub fn new(row: usize, message: String) -> Self {
+        RowError { row, message }
+    }
+
+    pub fn get_row(&self) -> usize {
+        self.row
+    }
+
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+}
+
+// 取り込み結果。対応するカテゴリーがなかった元のカテゴリー名は件数とともに報告する
+#[derive(Debug, PartialEq, Default)]
+pub struct ImportResult {
+    pub items: Vec<models::Item>,
+    pub errors: Vec<RowError>,
+    pub unmatched_categories: BTreeMap<String, usize>,
+}
+
+pub fn print_preview(result: &ImportResult, config: &Config) {
+    let ImportResult {
+        items,
+        errors,
+        unmatched_categories,
+    } = result;
+    println!("取り込み内容のプレビュー");
+    for item in items {
+        println!(
+            "  {} {}({}) {}",
+            item.get_date(),
+            item.get_name(),
+            item.get_item_category().get_label(),
+            config.currency.format(item.get_price_for_summary() as i64)
+        );
+    }
+    println!(
+        "取り込み対象: {}件, エラー: {}件",
+        items.len(),
+        errors.len()
+    );
+    for error in errors {
+        println!("  {}行目: {}", error.row, error.message);
+    }
+    if !unmatched_categories.is_empty() {
+        println!("対応するカテゴリーがないため既定のカテゴリーで取り込む項目:");
+        for (source, count) in unmatched_categories {
+            println!("  {}: {}件", source, count);
+        }
+        println!("マッピングの [category_map] に追加すると対応付けられます");
+    }
+}
+
+// ファイルを読み込んで取り込む。取り込み済みのファイルであれば何もしない
+pub fn run_file<F: FnOnce(&[u8]) -> ImportResult>(
+    config: &Config,
+    file_path: &str,
+    options: ImportOptions,
+    parse: F,
+) {
+    let bytes = fs::read(file_path).expect("取り込むファイルの読み込みに失敗しました");
+    let fingerprint = get_fingerprint(&bytes);
+    let history_path = services::io::get_sibling_path(&config.ledger_path, HISTORY_FILE);
+    let mut history: Vec<ImportRecord> = services::io::read_json_or_default(&history_path);
+    if let Some(record) = history
+        .iter()
+        .find(|record| record.fingerprint == fingerprint)
+    {
+        println!(
+            "このファイルは{}に取り込み済みです({})",
+            record.imported_at.format("%Y-%m-%d %H:%M"),
+            record.file
+        );
+        return;
+    }
+    let result = parse(&bytes);
+    let item_count = result.items.len();
+    if commit(result, config, options) {
+        let file = Path::new(file_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let message = format!("Import history: {}", file);
+        history.push(ImportRecord {
+            fingerprint,
+            file,
+            imported_at: Local::now().naive_local(),
+            item_count,
+        });
+        services::io::write_sidecar(&history, HISTORY_FILE, config, &message);
+    }
+}
+
+// プレビューを表示し、確認が取れたら家計簿に追加する。書き込んだ場合はtrueを返す
+pub fn commit(mut result: ImportResult, config: &Config, options: ImportOptions) -> bool {
+    if let Some(account) = &options.account {
+        services::account::find_or_panic(&config.ledger_path, account);
+        result.items = result
+            .items
+            .into_iter()
+            .map(|item| item.with_account(Some(account.clone())))
+            .collect();
+    }
+    print_preview(&result, config);
+    let mut data = services::io::read_data_or_create_new_data(&config.ledger_path);
+    let action = options.on_duplicate.unwrap_or(config.duplicate.action);
+    // 取り込む期間にかかるアーカイブ済みの項目も重複の候補にする(先頭archived_count件)
+    let archived = services::duplicate::read_archived_candidates(&result.items, config);
+    let archived_count = archived.len();
+    let existing: Vec<models::Item> = archived.into_iter().chain(data.iter().cloned()).collect();
+    let duplicates = services::duplicate::find_duplicates(
+        &result.items,
+        &existing,
+        config.duplicate.window_days,
+    );
+    print_duplicates(
+        &result.items,
+        &duplicates,
+        &existing,
+        archived_count,
+        action,
+    );
+    if options.dry_run {
+        println!("ドライランのため登録は行いません");
+        return false;
+    }
+    let has_changes = duplicates
+        .iter()
+        .any(|duplicate| match (duplicate, action) {
+            (None, _) | (Some(_), DuplicateAction::Keep) => true,
+            (Some(index), DuplicateAction::Merge) => *index >= archived_count,
+            (Some(_), DuplicateAction::Skip) => false,
+        });
+    if !has_changes {
+        println!("取り込む項目がありません");
+        return false;
+    }
+    if !options.assume_yes && !confirm("取り込みますか？(y/n)") {
+        println!("取り込みを中止しました");
+        return false;
+    }
+    let items = resolve_duplicates(&mut data, result.items, &duplicates, archived_count, action);
+    append_items(
+        &mut data,
+        items,
+        services::archive::get_next_id(&config.ledger_path),
+    );
+    services::io::write_to_json(&data, &config.ledger_path, config);
+    true
+}
+
+fn print_duplicates(
+    items: &[models::Item],
+    duplicates: &[Option<usize>],
+    existing: &[models::Item],
+    archived_count: usize,
+    action: DuplicateAction,
+) {
+    let pairs: Vec<(&models::Item, usize)> = items
+        .iter()
+        .zip(duplicates)
+        .filter_map(|(item, duplicate)| Some((item, (*duplicate)?)))
+        .collect();
+    if pairs.is_empty() {
+        return;
+    }
+    let label = match action {
+        DuplicateAction::Skip => "取り込みません",
+        DuplicateAction::Merge => "既存の項目を更新します",
+        DuplicateAction::Keep => "両方とも残します",
+    };
+    println!("重複の可能性がある項目: {}件({})", pairs.len(), label);
+    for (item, index) in pairs {
+        let note = if index < archived_count {
+            "(アーカイブ済み)"
+        } else {
+            ""
+        };
+        println!(
+            "  {} {} ≒ ID{} {} {}{}",
+            item.get_date(),
+            item.get_name(),
+            existing[index].get_id(),
+            existing[index].get_date(),
+            existing[index].get_name(),
+            note
+        );
+    }
+}
+
+// 重複の扱いに従って既存項目を更新し、新たに追加する項目を返す。
+// duplicatesの位置はアーカイブ済みのarchived_count件に続けてdataを並べたもの。アーカイブ済みの項目は更新しない
+fn resolve_duplicates(
+    data: &mut [models::Item],
+    items: Vec<models::Item>,
+    duplicates: &[Option<usize>],
+    archived_count: usize,
+    action: DuplicateAction,
+) -> Vec<models::Item> {
+    let mut new_items = Vec::new();
+    for (item, duplicate) in items.into_iter().zip(duplicates) {
+        match (duplicate, action) {
+            (Some(_), DuplicateAction::Skip) => {}
+            (Some(index), DuplicateAction::Merge) if *index >= archived_count => {
+                let index = index - archived_count;
+                data[index] = services::duplicate::merge(&data[index], item);
+            }
+            (Some(_), DuplicateAction::Merge) => {}
+            _ => new_items.push(item),
+        }
+    }
+    new_items
+}
+
+// OFX/QIFの小数を含む金額を円単位に丸める
+pub fn parse_decimal_amount(field: &str) -> Result<i64, String> {
+    let cleaned: String = field.chars().filter(|c| *c != ',').collect();
+    cleaned
+        .trim()
+        .parse::<f64>()
+        .map(|amount| amount.round() as i64)
+        .map_err(|_| format!("金額を解釈できません: {}", field))
+}
+
+// 符号付きの金額から項目を作る。正は収入、負は支出として既定のカテゴリー(その他)にする
+pub fn new_item(
+    name: &str,
+    amount: i64,
+    date: chrono::NaiveDate,
+    category: Option<&str>,
+) -> Result<models::Item, String> {
+    let price = u32::try_from(amount.unsigned_abs())
+        .map_err(|_| format!("金額が大きすぎます: {}", amount))?;
+    let category = if amount > 0 {
+        models::Category::Income(
+            category
+                .and_then(models::IncomeCategory::from_name)
+                .unwrap_or(models::IncomeCategory::Other),
+        )
+    } else {
+        models::Category::Expense(
+            category
+                .and_then(models::ExpenseCategory::from_name)
+                .unwrap_or(models::ExpenseCategory::Other),
+        )
+    };
+    Ok(models::Item::new(name.to_string(), category, price, date))
+}
+
+// ファイル内容のFNV-1aハッシュ
+fn get_fingerprint(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+fn append_items(data: &mut Vec<models::Item>, items: Vec<models::Item>, min_id: u32) {
+    let next_id = services::io::get_next_id(data).max(min_id);
+    for (id, item) in (next_id..).zip(items) {
+        data.push(item.with_id(id));
+    }
+}
+
+fn confirm(message: &str) -> bool {
+    println!("{}", message);
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .expect("入力に失敗しました");
+    answer.trim() == "y"
+}
+
+#[cfg(test)]
+mod import_test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn get_test_item(name: &str) -> models::Item {
+        models::Item::new(
+            name.to_string(),
+            models::Category::Expense(models::ExpenseCategory::Food),
+            800,
+            NaiveDate::from_ymd_opt(2025, 8, 1).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_append_items() {
+        let mut data = vec![get_test_item("既存").with_id(3)];
+        append_items(&mut data, vec![get_test_item("A"), get_test_item("B")], 1);
+        let ids: Vec<u32> = data.iter().map(|item| item.get_id()).collect();
+        assert_eq!(ids, vec![3, 4, 5]);
+
+        let mut data = Vec::new();
+        append_items(&mut data, vec![get_test_item("A")], 10);
+        assert_eq!(data[0].get_id(), 10);
+    }
+
+    #[test]
+    fn test_resolve_duplicates() {
+        let existing = vec![
+            get_test_item("既存").with_id(1),
+            get_test_item("B").with_id(2),
+        ];
+        let items = vec![
+            get_test_item("既存 新宿").with_external_id(Some("T1".to_string())),
+            get_test_item("新規"),
+        ];
+        let duplicates = vec![Some(0), None];
+
+        let mut data = existing.clone();
+        let new_items = resolve_duplicates(
+            &mut data,
+            items.clone(),
+            &duplicates,
+            0,
+            DuplicateAction::Skip,
+        );
+        assert_eq!(new_items, vec![get_test_item("新規")]);
+        assert_eq!(data, existing);
+
+        let new_items = resolve_duplicates(
+            &mut data,
+            items.clone(),
+            &duplicates,
+            0,
+            DuplicateAction::Merge,
+        );
+        assert_eq!(new_items.len(), 1);
+        assert_eq!(data[0].get_name(), "既存");
+        assert_eq!(data[0].get_external_id(), Some("T1"));
+        assert_eq!(data[0].get_id(), 1);
+
+        let mut data = existing.clone();
+        let new_items = resolve_duplicates(
+            &mut data,
+            items.clone(),
+            &duplicates,
+            0,
+            DuplicateAction::Keep,
+        );
+        assert_eq!(new_items.len(), 2);
+
+        // 先頭1件がアーカイブ済みの場合、重複先はdataの位置をずらして扱い、アーカイブ済みは更新しない
+        let mut data = existing.clone();
+        let new_items = resolve_duplicates(
+            &mut data,
+            items.clone(),
+            &[Some(1), None],
+            1,
+            DuplicateAction::Merge,
+        );
+        assert_eq!(new_items, vec![get_test_item("新規")]);
+        assert_eq!(data[0].get_external_id(), Some("T1"));
+
+        let mut data = existing.clone();
+        let new_items =
+            resolve_duplicates(&mut data, items, &duplicates, 1, DuplicateAction::Merge);
+        assert_eq!(new_items, vec![get_test_item("新規")]);
+        assert_eq!(data, existing);
+    }
+
+    #[test]
+    fn test_commit_archived_duplicate() {
+        let test_dir = std::env::temp_dir().join("kakeibo_import_archived_test");
+        let _ = fs::remove_dir_all(&test_dir);
+        fs::create_dir_all(&test_dir).unwrap();
+        let mut config = Config {
+            ledger_path: test_dir.join("data.json").to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+        config.backup.enabled = false;
+        services::io::write_to_json(
+            &[get_test_item("A").with_id(1)],
+            &config.ledger_path,
+            &config,
+        );
+        services::archive::archive_year(&config, 2025, 2026);
+
+        let options = ImportOptions {
+            assume_yes: true,
+            ..Default::default()
+        };
+        let result = ImportResult {
+            items: vec![get_test_item("A"), get_test_item("B")],
+            ..Default::default()
+        };
+        assert!(commit(result, &config, options));
+        let data = services::io::read_data(&config.ledger_path);
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].get_name(), "B");
+        assert_eq!(data[0].get_id(), 2);
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_run_file_twice() {
+        let test_dir = std::env::temp_dir().join("kakeibo_import_test");
+        let _ = fs::remove_dir_all(&test_dir);
+        fs::create_dir_all(&test_dir).unwrap();
+        let mut config = Config {
+            ledger_path: test_dir.join("data.json").to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+        config.backup.enabled = false;
+        let file_path = test_dir.join("statement.csv");
+        fs::write(&file_path, "dummy").unwrap();
+        let options = ImportOptions {
+            assume_yes: true,
+            ..Default::default()
+        };
+        let parse = |_: &[u8]| ImportResult {
+            items: vec![get_test_item("A"), get_test_item("B")],
+            ..Default::default()
+        };
+
+        run_file(&config, file_path.to_str().unwrap(), options.clone(), parse);
+        run_file(&config, file_path.to_str().unwrap(), options.clone(), parse);
+        assert_eq!(services::io::read_data(&config.ledger_path).len(), 2);
+        let history: Vec<ImportRecord> =
+            services::io::read_json_or_default(&test_dir.join(HISTORY_FILE));
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].file, "statement.csv");
+        assert_eq!(history[0].item_count, 2);
+
+        // 内容が変われば別のファイルとして扱い、既存項目との重複は取り込まない
+        fs::write(&file_path, "dummy2").unwrap();
+        run_file(&config, file_path.to_str().unwrap(), options, parse);
+        assert_eq!(services::io::read_data(&config.ledger_path).len(), 2);
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_decimal_amount() {
+        assert_eq!(parse_decimal_amount("-1,234.00"), Ok(-1234));
+        assert_eq!(parse_decimal_amount("300000"), Ok(300000));
+        assert!(parse_decimal_amount("abc").is_err());
+    }
+
+    #[test]
+    fn test_new_item() {
+        let date = NaiveDate::from_ymd_opt(2025, 8, 1).unwrap();
+        let item = new_item("ランチ", -800, date, Some("食費")).unwrap();
+        assert_eq!(item.get_price_for_summary(), -800);
+        assert_eq!(
+            *item.get_item_category(),
+            models::Category::Expense(models::ExpenseCategory::Food)
+        );
+        let item = new_item("返金", 500, date, Some("食費")).unwrap();
+        assert_eq!(
+            *item.get_item_category(),
+            models::Category::Income(models::IncomeCategory::Other)
+        );
+    }
+
+    #[test]
+    fn test_get_fingerprint() {
+        assert_eq!(get_fingerprint(b""), "cbf29ce484222325");
+        assert_eq!(get_fingerprint(b"a"), "af63dc4c8601ec8c");
+    }
+}
@@ -0,0 +1,135 @@
+use super::{ImportOptions, ImportResult, RowError};
+use crate::config::Config;
+use crate::models;
+use crate::services::io::TextEncoding;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+pub fn run(config: &Config, file_path: &str, options: ImportOptions) {
+    println!("OFXファイルを取り込みます");
+    super::run_file(config, file_path, options, parse);
+}
+
+// SGML形式(OFX 1.x)とXML形式(OFX 2.x)のどちらも、タグで区切ってSTMTTRNの中身を読む。
+// FITIDは取引IDとして残し、重複の判定に使う
+pub fn parse(bytes: &[u8]) -> ImportResult {
+    let content = TextEncoding::detect(bytes).decode(bytes);
+    let mut result = ImportResult::default();
+    let mut transaction: Option<(usize, HashMap<&str, String>)> = None;
+    let mut segments = content.split('<');
+    let mut line = 1 + segments.next().unwrap_or("").matches('\n').count();
+    for segment in segments {
+        let (tag, value) = segment.split_once('>').unwrap_or((segment, ""));
+        match tag.trim() {
+            "STMTTRN" => transaction = Some((line, HashMap::new())),
+            "/STMTTRN" => {
+                if let Some((row, fields)) = transaction.take() {
+                    match parse_transaction(&fields) {
+                        Ok(Some(item)) => result.items.push(item),
+                        Ok(None) => {}
+                        Err(message) => result.errors.push(RowError::new(row, message)),
+                    }
+                }
+            }
+            tag if !tag.starts_with('/') => {
+                if let Some((_, fields)) = transaction.as_mut() {
+                    fields.insert(tag, unescape(value.trim()));
+                }
+            }
+            _ => {}
+        }
+        line += segment.matches('\n').count();
+    }
+    result
+}
+
+// 品目名はNAME、なければMEMOを使う。MEMOがカテゴリー名であればそのカテゴリーにする
+fn parse_transaction(fields: &HashMap<&str, String>) -> Result<Option<models::Item>, String> {
+    let get = |tag: &str| fields.get(tag).map(|value| value.as_str()).unwrap_or("");
+
+    let date_field = get("DTPOSTED");
+    let date = date_field
+        .get(..8)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .ok_or_else(|| format!("日付を解釈できません: {}", date_field))?;
+    let amount = super::parse_decimal_amount(get("TRNAMT"))?;
+    if amount == 0 {
+        return Ok(None);
+    }
+    let name = match get("NAME") {
+        "" => get("MEMO"),
+        name => name,
+    };
+    if name.is_empty() {
+        return Err("品目名が空です".to_string());
+    }
+    let fitid = Some(get("FITID"))
+        .filter(|fitid| !fitid.is_empty())
+        .map(|fitid| fitid.to_string());
+    let item = super::new_item(name, amount, date, Some(get("MEMO")))?;
+    Ok(Some(item.with_external_id(fitid)))
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod ofx_test {
+    use super::*;
+
+    #[test]
+    fn test_parse_sgml() {
+        let ofx = "OFXHEADER:100\nDATA:OFXSGML\nVERSION:102\n\n\
+                   <OFX>\n<BANKMSGSRSV1>\n<STMTTRNRS>\n<STMTRS>\n<CURDEF>JPY\n<BANKTRANLIST>\n\
+                   <STMTTRN>\n<TRNTYPE>DEBIT\n<DTPOSTED>20250801120000[+9:JST]\n<TRNAMT>-800.00\n\
+                   <FITID>202508010001\n<NAME>ランチ &amp; カフェ\n</STMTTRN>\n\
+                   <STMTTRN>\n<TRNTYPE>CREDIT\n<DTPOSTED>20250825\n<TRNAMT>300000\n\
+                   <FITID>202508250001\n<MEMO>給与\n</STMTTRN>\n\
+                   <STMTTRN>\n<TRNTYPE>DEBIT\n<DTPOSTED>2025\n<TRNAMT>-1\n<NAME>不明\n</STMTTRN>\n\
+                   </BANKTRANLIST>\n</STMTRS>\n</STMTTRNRS>\n</BANKMSGSRSV1>\n</OFX>\n";
+        let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode(ofx);
+        let result = parse(&bytes);
+
+        assert_eq!(result.items.len(), 2);
+        assert_eq!(result.items[0].get_name(), "ランチ & カフェ");
+        assert_eq!(result.items[0].get_price_for_summary(), -800);
+        assert_eq!(result.items[0].get_external_id(), Some("202508010001"));
+        assert_eq!(
+            result.items[0].get_date(),
+            NaiveDate::from_ymd_opt(2025, 8, 1).unwrap()
+        );
+        assert_eq!(result.items[1].get_name(), "給与");
+        assert_eq!(
+            *result.items[1].get_item_category(),
+            models::Category::Income(models::IncomeCategory::Salary)
+        );
+        assert_eq!(
+            result.errors,
+            vec![RowError::new(25, "日付を解釈できません: 2025".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_parse_xml() {
+        let ofx = r#"<?xml version="1.0" encoding="UTF-8"?>
+<?OFX OFXHEADER="200" VERSION="211"?>
+<OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS><BANKTRANLIST>
+<STMTTRN><TRNTYPE>DEBIT</TRNTYPE><DTPOSTED>20250803</DTPOSTED><TRNAMT>-1200</TRNAMT><FITID>X1</FITID><NAME>書店</NAME><MEMO>趣味</MEMO></STMTTRN>
+</BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>"#;
+        let result = parse(ofx.as_bytes());
+
+        assert!(result.errors.is_empty());
+        assert_eq!(result.items[0].get_name(), "書店");
+        assert_eq!(
+            *result.items[0].get_item_category(),
+            models::Category::Expense(models::ExpenseCategory::Hobby)
+        );
+        assert_eq!(result.items[0].get_external_id(), Some("X1"));
+    }
+}
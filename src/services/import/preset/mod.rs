@@ -0,0 +1,196 @@
+use super::csv::CsvMapping;
+use crate::config;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const USER_PRESET_DIR: &str = "presets";
+
+// 組み込みのプリセット。同名のファイルをユーザーのプリセットディレクトリに置くと上書きできる
+const BUILTIN_PRESETS: [(&str, &str); 4] = [
+    ("jp-bank", include_str!("../../../../presets/jp-bank.toml")),
+    ("jp-card", include_str!("../../../../presets/jp-card.toml")),
+    (
+        "moneyforward",
+        include_str!("../../../../presets/moneyforward.toml"),
+    ),
+    ("zaim", include_str!("../../../../presets/zaim.toml")),
+];
+
+pub fn run_list() {
+    println!("取り込みプリセット一覧");
+    for (name, (source, content)) in list_presets(get_user_preset_dir().as_deref()) {
+        let mapping = CsvMapping::from_toml(&content);
+        println!(
+            "{} [{}] {}",
+            name,
+            source,
+            mapping.description.as_deref().unwrap_or("")
+        );
+    }
+    if let Some(dir) = get_user_preset_dir() {
+        println!("独自のプリセットは {} に配置してください", dir.display());
+    }
+}
+
+pub fn load(name: &str) -> CsvMapping {
+    load_from(name, get_user_preset_dir().as_deref())
+}
+
+// user_dirがNoneなら組み込みのプリセットだけから探す
+fn load_from(name: &str, user_dir: Option<&Path>) -> CsvMapping {
+    let presets = list_presets(user_dir);
+    let (_, content) = presets
+        .get(name)
+        .unwrap_or_else(|| panic!("プリセットが見つかりません: {}", name));
+    CsvMapping::from_toml(content)
+}
+
+// プリセット名から(提供元, 定義内容)を引けるようにする
+fn list_presets(user_dir: Option<&Path>) -> BTreeMap<String, (&'static str, String)> {
+    let mut presets: BTreeMap<String, (&'static str, String)> = BUILTIN_PRESETS
+        .iter()
+        .map(|(name, content)| (name.to_string(), ("組み込み", content.to_string())))
+        .collect();
+    let entries = user_dir.and_then(|dir| fs::read_dir(dir).ok());
+    for entry in entries.into_iter().flatten().filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "toml")
+        {
+            let name = path.file_stem().unwrap().to_string_lossy().to_string();
+            let content =
+                fs::read_to_string(&path).expect("プリセットファイルの読み込みに失敗しました");
+            presets.insert(name, ("ユーザー", content));
+        }
+    }
+    presets
+}
+
+fn get_user_preset_dir() -> Option<PathBuf> {
+    Some(config::get_user_config_dir()?.join(USER_PRESET_DIR))
+}
+
+#[cfg(test)]
+mod preset_test {
+    use super::*;
+    use crate::models;
+    use crate::services::import::ImportResult;
+    use crate::services::import::csv::parse;
+
+    #[test]
+    fn test_builtin_presets() {
+        for (name, content) in BUILTIN_PRESETS {
+            let mapping = CsvMapping::from_toml(content);
+            assert!(mapping.description.is_some(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_jp_card() {
+        let csv = "利用日,利用店名,利用金額\n2025/8/1,セブンイレブン,498\n2025/08/15,書店,1980\n";
+        let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode(csv);
+        let ImportResult { items, errors, .. } = parse(&bytes, &load_from("jp-card", None));
+
+        assert!(errors.is_empty());
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].get_price_for_summary(), -498);
+        assert_eq!(
+            *items[1].get_item_category(),
+            models::Category::Expense(models::ExpenseCategory::Other)
+        );
+    }
+
+    #[test]
+    fn test_jp_bank() {
+        let csv = "日付,摘要,お預り金額,お引出し金額,残高\n\
+                   2025/08/25,給与 カ)サンプル,\"300,000\",,\"350,000\"\n\
+                   2025/12/10,賞与,\"500,000\",,\"850,000\"\n\
+                   2025/08/27,ATM,,\"20,000\",\"330,000\"\n";
+        let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode(csv);
+        let ImportResult { items, errors, .. } = parse(&bytes, &load_from("jp-bank", None));
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            *items[0].get_item_category(),
+            models::Category::Income(models::IncomeCategory::Salary)
+        );
+        assert_eq!(
+            *items[1].get_item_category(),
+            models::Category::Income(models::IncomeCategory::Bonus)
+        );
+        assert_eq!(items[2].get_price_for_summary(), -20000);
+    }
+
+    #[test]
+    fn test_zaim() {
+        let csv = "日付,方法,カテゴリ,カテゴリの内訳,支払元,入金先,品目,メモ,お店,通貨,収入,支出,振替,残高調整,通貨変換前の金額,集計の設定\n\
+                   2025-08-01,payment,食費,カフェ,財布,,,,スタバ,JPY,0,550,0,0,,\n\
+                   2025-08-02,payment,ペット,ペットフード,財布,,猫の餌,,,JPY,0,2000,0,0,,\n\
+                   2025-08-25,income,給与所得,給与所得,,銀行,,,,JPY,300000,0,0,0,,\n\
+                   2025-08-26,transfer,-,-,銀行,財布,,,,JPY,0,0,20000,0,,\n";
+        let result = parse(csv.as_bytes(), &load_from("zaim", None));
+
+        assert!(result.errors.is_empty());
+        assert_eq!(result.items.len(), 3);
+        assert_eq!(result.items[0].get_name(), "カフェ");
+        assert_eq!(
+            *result.items[0].get_item_category(),
+            models::Category::Expense(models::ExpenseCategory::Food)
+        );
+        assert_eq!(
+            *result.items[2].get_item_category(),
+            models::Category::Income(models::IncomeCategory::Salary)
+        );
+        assert_eq!(
+            result.unmatched_categories,
+            BTreeMap::from([("ペット/ペットフード".to_string(), 1)])
+        );
+    }
+
+    #[test]
+    fn test_moneyforward() {
+        let csv = "計算対象,日付,内容,金額（円）,保有金融機関,大項目,中項目,メモ,振替,ID\n\
+                   1,2025/08/01,ランチ,-1200,カード,食費,外食,,0,a1\n\
+                   1,2025/08/25,給与 カ)サンプル,300000,銀行,収入,給与,,0,a2\n\
+                   1,2025/08/27,カード引き落とし,-50000,銀行,未分類,未分類,,1,a3\n\
+                   0,2025/08/28,立替,-3000,財布,食費,外食,,0,a4\n";
+        let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode(csv);
+        let result = parse(&bytes, &load_from("moneyforward", None));
+
+        assert!(result.errors.is_empty());
+        assert_eq!(result.items.len(), 2);
+        assert_eq!(result.items[0].get_price_for_summary(), -1200);
+        assert_eq!(
+            *result.items[1].get_item_category(),
+            models::Category::Income(models::IncomeCategory::Salary)
+        );
+        assert!(result.unmatched_categories.is_empty());
+    }
+
+    #[test]
+    fn test_load_user_preset() {
+        let test_dir = std::env::temp_dir().join("kakeibo_preset_test");
+        let _ = fs::remove_dir_all(&test_dir);
+        fs::create_dir_all(&test_dir).unwrap();
+        let content = BUILTIN_PRESETS[1]
+            .1
+            .replace("encoding = \"shift_jis\"", "encoding = \"utf8\"");
+        fs::write(test_dir.join("jp-card.toml"), &content).unwrap();
+        fs::write(test_dir.join("memo.txt"), "").unwrap();
+
+        let presets = list_presets(Some(&test_dir));
+        assert_eq!(presets.len(), BUILTIN_PRESETS.len());
+        assert_eq!(presets["jp-card"], ("ユーザー", content));
+        assert_eq!(presets["zaim"].0, "組み込み");
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "プリセットが見つかりません: unknown")]
+    fn test_load_unknown() {
+        load_from("unknown", None);
+    }
+}
@@ -0,0 +1,142 @@
+use super::{ImportOptions, ImportResult, RowError};
+use crate::config::Config;
+use crate::models;
+use crate::services::io::TextEncoding;
+use chrono::{Datelike, NaiveDate};
+
+pub fn run(config: &Config, file_path: &str, options: ImportOptions) {
+    println!("QIFファイルを取り込みます");
+    super::run_file(config, file_path, options, parse);
+}
+
+// 1行目の文字が項目の種類を表し、"^"で1件の取引が終わる
+pub fn parse(bytes: &[u8]) -> ImportResult {
+    let content = TextEncoding::detect(bytes).decode(bytes);
+    let mut result = ImportResult::default();
+    let mut record: Vec<(char, &str)> = Vec::new();
+    let mut first_row = 0;
+    let mut push_record = |row: usize, record: &[(char, &str)]| {
+        if record.is_empty() {
+            return;
+        }
+        match parse_record(record) {
+            Ok(Some(item)) => result.items.push(item),
+            Ok(None) => {}
+            Err(message) => result.errors.push(RowError::new(row, message)),
+        }
+    };
+    for (row, line) in (1..).zip(content.lines()) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('!') {
+            continue;
+        }
+        if line == "^" {
+            push_record(first_row, &record);
+            record.clear();
+            continue;
+        }
+        if record.is_empty() {
+            first_row = row;
+        }
+        let mut chars = line.chars();
+        let code = chars.next().unwrap();
+        record.push((code, chars.as_str().trim()));
+    }
+    push_record(first_row, &record);
+    result
+}
+
+// 品目名はP(支払先)、なければM(メモ)を使う。L(カテゴリー)は"食費:外食"のような階層の先頭で判定する
+fn parse_record(record: &[(char, &str)]) -> Result<Option<models::Item>, String> {
+    let get = |code: char| {
+        record
+            .iter()
+            .find(|(field_code, _)| *field_code == code)
+            .map(|(_, value)| *value)
+            .unwrap_or("")
+    };
+
+    let date = parse_date(get('D'))?;
+    let amount_field = match get('T') {
+        "" => get('U'),
+        amount => amount,
+    };
+    let amount = super::parse_decimal_amount(amount_field)?;
+    if amount == 0 {
+        return Ok(None);
+    }
+    let name = match get('P') {
+        "" => get('M'),
+        name => name,
+    };
+    if name.is_empty() {
+        return Err("品目名が空です".to_string());
+    }
+    let category = get('L').split(':').next();
+    super::new_item(name, amount, date, category).map(Some)
+}
+
+// 2025-08-01, 2025/08/01, 08/01/2025, 8/ 1'25 などの形式を読む
+fn parse_date(field: &str) -> Result<NaiveDate, String> {
+    let normalized: String = field
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| if c == '\'' { '/' } else { c })
+        .collect();
+    let date = ["%Y-%m-%d", "%m/%d/%Y", "%Y/%m/%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(&normalized, format).ok())
+        .ok_or_else(|| format!("日付を解釈できません: {}", field))?;
+    // 2桁の年は2000年代とみなす
+    if date.year() < 100 {
+        Ok(date.with_year(date.year() + 2000).unwrap())
+    } else {
+        Ok(date)
+    }
+}
+
+#[cfg(test)]
+mod qif_test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let qif = "!Type:Bank\nD08/01/2025\nT-800.00\nPランチ\nL食費:外食\n^\n\
+                   D8/25'25\nU300,000.00\nM給料\nLSalary\n^\n\
+                   D2025-08-30\nT-1500\nP書店\nLBooks\n^\n\
+                   Dあした\nT-100\nP不明\n^\n";
+        let result = parse(qif.as_bytes());
+
+        assert_eq!(result.items.len(), 3);
+        assert_eq!(
+            *result.items[0].get_item_category(),
+            models::Category::Expense(models::ExpenseCategory::Food)
+        );
+        assert_eq!(result.items[1].get_name(), "給料");
+        assert_eq!(result.items[1].get_price_for_summary(), 300000);
+        assert_eq!(
+            result.items[1].get_date(),
+            NaiveDate::from_ymd_opt(2025, 8, 25).unwrap()
+        );
+        assert_eq!(
+            *result.items[2].get_item_category(),
+            models::Category::Expense(models::ExpenseCategory::Other)
+        );
+        assert_eq!(
+            result.errors,
+            vec![RowError::new(
+                17,
+                "日付を解釈できません: あした".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let expected = NaiveDate::from_ymd_opt(2025, 8, 1).unwrap();
+        assert_eq!(parse_date("2025/08/01"), Ok(expected));
+        assert_eq!(parse_date(" 8/ 1'25"), Ok(expected));
+        assert_eq!(parse_date("08/01/2025"), Ok(expected));
+        assert!(parse_date("").is_err());
+    }
+}
@@ -1,7 +1,59 @@
-use crate::models;
+use crate::config::Config;
+use crate::{models, services};
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::BufReader;
 use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TextEncoding {
+    #[default]
+    #[serde(alias = "utf-8")]
+    Utf8,
+    #[serde(alias = "utf8-bom", alias = "utf-8-bom")]
+    Utf8Bom,
+    #[serde(alias = "shift-jis", alias = "sjis", alias = "cp932")]
+    ShiftJis,
+}
+
+impl TextEncoding {
+    // UTF-8のBOMは取り除き、Shift_JISはWindows-31Jとして読む
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        match self {
+            TextEncoding::Utf8 | TextEncoding::Utf8Bom => encoding_rs::UTF_8
+                .decode_with_bom_removal(bytes)
+                .0
+                .into_owned(),
+            TextEncoding::ShiftJis => encoding_rs::SHIFT_JIS.decode(bytes).0.into_owned(),
+        }
+    }
+
+    // Excelで開けるよう、UTF-8にはBOMを付けることもできる
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        match self {
+            TextEncoding::Utf8 => text.as_bytes().to_vec(),
+            TextEncoding::Utf8Bom => [b"\xEF\xBB\xBF".as_slice(), text.as_bytes()].concat(),
+            TextEncoding::ShiftJis => encoding_rs::SHIFT_JIS.encode(text).0.into_owned(),
+        }
+    }
+
+    // 文字コードの指定がないファイル向け。UTF-8として読めなければShift_JISとみなす
+    pub fn detect(bytes: &[u8]) -> Self {
+        if std::str::from_utf8(bytes).is_ok() {
+            TextEncoding::Utf8
+        } else {
+            TextEncoding::ShiftJis
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::deserialize(toml::Value::String(name.to_string())).ok()
+    }
+}
 
 pub fn read_data_or_create_new_data(file_path: &str) -> Vec<models::Item> {
     let file = File::open(file_path);
@@ -18,21 +70,144 @@ pub fn read_data_or_create_new_data(file_path: &str) -> Vec<models::Item> {
 }
 
 pub fn read_data_or_panic(file_path: &str) -> Vec<models::Item> {
-    let file = File::open(file_path).expect("ファイルがオープンできませんでした");
-    let buf_reader = BufReader::new(file);
-    let data: Vec<models::Item> =
-        serde_json::from_reader(buf_reader).expect("デシリアライズに失敗しました");
-    if data.len() == 0 {
+    let data = read_data(file_path);
+    if data.is_empty() {
         panic!("データが存在しません");
     }
     data
 }
 
-pub fn write_to_json(data: &Vec<models::Item>, file_path: &str) {
-    let json_data = serde_json::to_string_pretty(data).expect("JSONのシリアライズに失敗しました");
-    let mut file = File::create(file_path).expect("書き込みファイルのオープンに失敗しました");
-    writeln!(file, "{}", json_data).expect("ファイルへの書き込みに失敗しました");
+// アーカイブ済みの年も含めて全期間のデータを読み込む
+pub fn read_data_with_archives_or_panic(file_path: &str) -> Vec<models::Item> {
+    let mut data = services::archive::read_archived_data(file_path, |_| true);
+    data.extend(read_data(file_path));
+    if data.is_empty() {
+        panic!("データが存在しません");
+    }
+    data
+}
+
+// 指定した年度のアーカイブだけを追加で読み込む
+pub fn read_data_for_years(file_path: &str, from_year: i32, to_year: i32) -> Vec<models::Item> {
+    let mut data = services::archive::read_archived_data(file_path, |year| {
+        (from_year..=to_year).contains(&year)
+    });
+    data.extend(read_data(file_path));
+    data
+}
+
+// fromからtoまでの日付を含む年度のアーカイブだけを追加で読み込む。Noneの側は期間を区切らない
+pub fn read_data_between(
+    config: &Config,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Vec<models::Item> {
+    let from_year = from.map_or(i32::MIN, |date| config.get_fiscal_year(date));
+    let to_year = to.map_or(i32::MAX, |date| config.get_fiscal_year(date));
+    read_data_for_years(&config.ledger_path, from_year, to_year)
+}
+
+pub fn read_data(file_path: &str) -> Vec<models::Item> {
+    let file = File::open(file_path).expect("ファイルがオープンできませんでした");
+    let buf_reader = BufReader::new(file);
+    serde_json::from_reader(buf_reader).expect("デシリアライズに失敗しました")
+}
+
+pub fn write_to_json(data: &[models::Item], file_path: &str, config: &Config) {
+    let before = read_data_or_empty(file_path);
+    let message = if config.git.enabled {
+        Some(services::git::build_message(
+            &before,
+            data,
+            &config.currency,
+        ))
+    } else {
+        None
+    };
+    write_items(data, Path::new(file_path));
+    if config.backup.enabled {
+        services::backup::create_snapshot(file_path, &config.backup);
+        services::backup::prune_snapshots(file_path, &config.backup);
+    }
+    if let Some(message) = message {
+        services::git::commit(&config.ledger_path, &message);
+    }
     println!("項目の登録が完了しました");
+    let (added, removed) = diff_items(&before, data);
+    let changed: Vec<&models::Item> = added.into_iter().chain(removed).collect();
+    services::reconcile::warn_broken_assertions(config, &changed);
+}
+
+// バックアップやコミットをせずに項目だけを書き込む。差分が見やすいよう日付・ID順に並べる
+pub fn write_items(data: &[models::Item], path: &Path) {
+    let mut sorted: Vec<&models::Item> = data.iter().collect();
+    sorted.sort_by_key(|item| (item.get_date(), item.get_id()));
+    write_json(&sorted, path);
+}
+
+fn read_data_or_empty(file_path: &str) -> Vec<models::Item> {
+    match File::open(file_path) {
+        Ok(f) => serde_json::from_reader(BufReader::new(f)).unwrap_or_default(),
+        Err(_) => Vec::new(),
+    }
+}
+
+// fromをtoにしたときに増える項目と消える項目を返す
+pub fn diff_items<'a>(
+    from: &'a [models::Item],
+    to: &'a [models::Item],
+) -> (Vec<&'a models::Item>, Vec<&'a models::Item>) {
+    let mut unmatched_from: Vec<&models::Item> = from.iter().collect();
+    let mut added = Vec::new();
+    for item in to {
+        match unmatched_from.iter().position(|other| *other == item) {
+            Some(index) => {
+                unmatched_from.remove(index);
+            }
+            None => added.push(item),
+        }
+    }
+    (added, unmatched_from)
+}
+
+pub fn get_next_id(data: &[models::Item]) -> u32 {
+    data.iter().map(|item| item.get_id()).max().unwrap_or(0) + 1
+}
+
+// 家計簿ファイルと同じディレクトリに置く付随ファイルのパス
+pub fn get_sibling_path(file_path: &str, file_name: &str) -> PathBuf {
+    Path::new(file_path)
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(file_name)
+}
+
+pub fn read_json_or_default<T: DeserializeOwned + Default>(path: &Path) -> T {
+    match File::open(path) {
+        Ok(f) => serde_json::from_reader(BufReader::new(f)).expect("デシリアライズに失敗しました"),
+        Err(_) => T::default(),
+    }
+}
+
+pub fn write_json<T: Serialize>(value: &T, path: &Path) {
+    let json_data = serde_json::to_string_pretty(value).expect("JSONのシリアライズに失敗しました");
+    let mut file = File::create(path).expect("書き込みファイルのオープンに失敗しました");
+    writeln!(file, "{}", json_data).expect("ファイルへの書き込みに失敗しました");
+}
+
+// 家計簿と同じディレクトリの付随ファイル(口座・振替・予算など)を書き込む。
+// 家計簿と同じようにバックアップを作り、git連携ではmessageでコミットする
+pub fn write_sidecar<T: Serialize>(value: &T, file_name: &str, config: &Config, message: &str) {
+    let path = get_sibling_path(&config.ledger_path, file_name);
+    write_json(value, &path);
+    if config.backup.enabled {
+        let path = path.to_str().expect("ファイル名が不正です");
+        services::backup::create_snapshot(path, &config.backup);
+        services::backup::prune_snapshots(path, &config.backup);
+    }
+    if config.git.enabled {
+        services::git::commit(&config.ledger_path, message);
+    }
 }
 
 #[cfg(test)]
@@ -41,6 +216,12 @@ mod io_test {
     use chrono::NaiveDate;
     use std::fs;
 
+    fn get_test_config() -> Config {
+        let mut config = Config::default();
+        config.backup.enabled = false;
+        config
+    }
+
     fn create_test_data() -> Vec<models::Item> {
         vec![models::Item::new(
             "テスト".to_string(),
@@ -56,7 +237,7 @@ mod io_test {
         let test_file = "test_data.json";
 
         // テストファイルを作成
-        write_to_json(&test_data, test_file);
+        write_to_json(&test_data, test_file, &get_test_config());
 
         let result = read_data_or_create_new_data(test_file);
         assert_eq!(result.len(), 1);
@@ -80,7 +261,7 @@ mod io_test {
         let test_file = "test_data.json";
 
         // テストファイルを作成
-        write_to_json(&test_data, test_file);
+        write_to_json(&test_data, test_file, &get_test_config());
 
         let result = read_data_or_panic(test_file);
         assert_eq!(result.len(), 1);
@@ -103,7 +284,7 @@ mod io_test {
         let empty_data: Vec<models::Item> = vec![];
 
         // 空のファイルを作成
-        write_to_json(&empty_data, test_file);
+        write_to_json(&empty_data, test_file, &get_test_config());
 
         read_data_or_panic(test_file);
 
@@ -116,7 +297,7 @@ mod io_test {
         let test_data = create_test_data();
         let test_file = "write_test_data.json";
 
-        write_to_json(&test_data, test_file);
+        write_to_json(&test_data, test_file, &get_test_config());
 
         // 書き込まれたファイルを読み込んで検証
         let result = read_data_or_create_new_data(test_file);
@@ -126,4 +307,140 @@ mod io_test {
         // テストファイルを削除
         fs::remove_file(test_file).unwrap();
     }
+
+    #[test]
+    fn test_diff_items() {
+        let lunch = || {
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                800,
+                NaiveDate::from_ymd_opt(2025, 8, 1).unwrap(),
+            )
+        };
+        let current = vec![lunch(), lunch()];
+        let restored = vec![lunch(), create_test_data().remove(0)];
+
+        let (added, removed) = diff_items(&current, &restored);
+        assert_eq!(added, vec![&restored[1]]);
+        assert_eq!(removed, vec![&current[1]]);
+    }
+
+    #[test]
+    fn test_write_to_json_sorted() {
+        let test_dir = std::env::temp_dir().join("kakeibo_io_sort_test");
+        let _ = fs::remove_dir_all(&test_dir);
+        fs::create_dir_all(&test_dir).unwrap();
+        let test_file = test_dir.join("data.json");
+        let test_file = test_file.to_str().unwrap();
+
+        let item = |id: u32, day: u32| {
+            models::Item::new(
+                format!("項目{}", id),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                100,
+                NaiveDate::from_ymd_opt(2025, 8, day).unwrap(),
+            )
+            .with_id(id)
+        };
+        let data = vec![item(3, 2), item(2, 1), item(1, 2)];
+        write_to_json(&data, test_file, &get_test_config());
+
+        let result: Vec<u32> = read_data(test_file)
+            .iter()
+            .map(|item| item.get_id())
+            .collect();
+        assert_eq!(result, vec![2, 1, 3]);
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_get_next_id() {
+        assert_eq!(get_next_id(&[]), 1);
+
+        let data = vec![
+            create_test_data().remove(0).with_id(3),
+            create_test_data().remove(0).with_id(7),
+        ];
+        assert_eq!(get_next_id(&data), 8);
+    }
+
+    #[test]
+    fn test_text_encoding_decode() {
+        let bom_utf8 = [b"\xEF\xBB\xBF".as_slice(), "利用日".as_bytes()].concat();
+        assert_eq!(TextEncoding::Utf8.decode(&bom_utf8), "利用日");
+
+        let (shift_jis, _, _) = encoding_rs::SHIFT_JIS.encode("利用店名,利用金額");
+        assert_eq!(
+            TextEncoding::ShiftJis.decode(&shift_jis),
+            "利用店名,利用金額"
+        );
+    }
+
+    #[test]
+    fn test_text_encoding_encode() {
+        assert_eq!(TextEncoding::Utf8.encode("品目"), "品目".as_bytes());
+        assert_eq!(&TextEncoding::Utf8Bom.encode("品目")[..3], b"\xEF\xBB\xBF");
+        let encoded = TextEncoding::ShiftJis.encode("品目,金額");
+        assert_eq!(TextEncoding::ShiftJis.decode(&encoded), "品目,金額");
+        assert_eq!(
+            TextEncoding::from_name("utf8-bom"),
+            Some(TextEncoding::Utf8Bom)
+        );
+        assert_eq!(
+            TextEncoding::from_name("cp932"),
+            Some(TextEncoding::ShiftJis)
+        );
+        assert_eq!(TextEncoding::from_name("latin1"), None);
+        assert_eq!(TextEncoding::detect("品目".as_bytes()), TextEncoding::Utf8);
+        assert_eq!(TextEncoding::detect(&encoded), TextEncoding::ShiftJis);
+    }
+
+    #[test]
+    fn test_get_sibling_path() {
+        assert_eq!(
+            get_sibling_path("store/data.json", "archives.json"),
+            PathBuf::from("store/archives.json")
+        );
+        assert_eq!(
+            get_sibling_path("data.json", "archives.json"),
+            PathBuf::from("archives.json")
+        );
+    }
+
+    #[test]
+    fn test_read_json_or_default() {
+        let test_file = std::env::temp_dir().join("kakeibo_io_json_test.json");
+        let _ = fs::remove_file(&test_file);
+        let result: Vec<u32> = read_json_or_default(&test_file);
+        assert!(result.is_empty());
+
+        write_json(&vec![1, 2, 3], &test_file);
+        let result: Vec<u32> = read_json_or_default(&test_file);
+        assert_eq!(result, vec![1, 2, 3]);
+
+        fs::remove_file(&test_file).unwrap();
+    }
+
+    #[test]
+    fn test_write_to_json_with_backup() {
+        let test_dir = std::env::temp_dir().join("kakeibo_io_backup_test");
+        let _ = fs::remove_dir_all(&test_dir);
+        fs::create_dir_all(&test_dir).unwrap();
+        let test_file = test_dir.join("data.json");
+        let test_file = test_file.to_str().unwrap();
+        let config = Config::default();
+
+        write_to_json(&create_test_data(), test_file, &config);
+
+        let snapshots = services::backup::list_snapshots(test_file, &config.backup);
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(
+            read_data(snapshots[0].get_path().to_str().unwrap()).len(),
+            1
+        );
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
 }
@@ -1,5 +1,20 @@
+pub mod account;
+pub mod archive;
+pub mod backup;
+pub mod budget;
+pub mod card;
+pub mod check;
+pub mod duplicate;
+pub mod envelope;
+pub mod export;
+pub mod git;
+pub mod import;
 pub mod io;
+pub mod reconcile;
+pub mod recurring;
 pub mod register;
+pub mod report;
 pub mod statistics;
 pub mod summarize;
+pub mod transfer;
 pub mod validate;
@@ -0,0 +1,435 @@
+use crate::config::Config;
+use crate::services::account::Account;
+use crate::services::transfer::Transfer;
+use crate::{models, services};
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::str::FromStr;
+
+pub const RECONCILIATION_FILE: &str = "reconciliations.json";
+
+// 照合で一致した残高。dateまでの照合済みの項目と振替から計算した残高がbalanceと一致するはず
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Assertion {
+    account: String,
+    date: NaiveDate,
+    balance: i64,
+}
+
+// 照合後の編集で記録した残高と合わなくなったもの
+#[derive(Debug, PartialEq)]
+pub struct BrokenAssertion {
+    pub account: String,
+    pub date: NaiveDate,
+    pub expected: i64,
+    pub actual: i64,
+}
+
+impl Assertion {
+    pub fn new(account: String, date: NaiveDate, balance: i64) -> Self {
+        Assertion {
+            account,
+            date,
+            balance,
+        }
+    }
+}
+
+impl BrokenAssertion {
+    pub fn describe(&self, config: &Config) -> String {
+        format!(
+            "{}の{}時点の残高が照合時と一致しません: 照合時{} 現在{}(差額{})",
+            self.account,
+            self.date,
+            config.currency.format(self.expected),
+            config.currency.format(self.actual),
+            services::summarize::format_price(self.actual - self.expected, &config.currency)
+        )
+    }
+}
+
+// 明細の残高と照合済みの残高が一致するまで、項目を1件ずつ照合済みにする
+pub fn run(config: &Config, name: &str, balance: &str, date: Option<&str>) {
+    let account = services::account::find_or_panic(&config.ledger_path, name);
+    let balance: i64 = balance.parse().expect("残高は数値で入力してください");
+    let date = date.map_or(Local::now().date_naive(), |date| {
+        NaiveDate::from_str(date).expect("日付はyyyy-mm-ddの形式で入力してください")
+    });
+    let archived = services::archive::read_archived_data(&config.ledger_path, |_| true);
+    let mut ledger = services::io::read_data_or_create_new_data(&config.ledger_path);
+    let transfers = services::transfer::read_transfers(&config.ledger_path);
+    let mut changed = false;
+    let mut matched = false;
+    println!("{}の{}時点の残高を照合します", name, date);
+    loop {
+        let data: Vec<models::Item> = archived.iter().chain(ledger.iter()).cloned().collect();
+        let cleared = get_cleared_balance(&account, &data, &transfers, date);
+        let difference = balance - cleared;
+        println!(
+            "明細の残高: {} 照合済みの残高: {} 差額: {}",
+            config.currency.format(balance),
+            config.currency.format(cleared),
+            services::summarize::format_price(difference, &config.currency)
+        );
+        if difference == 0 {
+            matched = true;
+            break;
+        }
+        let uncleared = get_uncleared(&account, &ledger, date);
+        if uncleared.is_empty() {
+            println!("照合していない項目がありません。明細と家計簿の差を確認してください");
+            break;
+        }
+        for item in &uncleared {
+            println!(
+                "[{}] {} {} {}",
+                item.get_id(),
+                item.get_date(),
+                item.get_name(),
+                services::summarize::format_price(
+                    item.get_price_for_summary() as i64,
+                    &config.currency
+                )
+            );
+        }
+        let input = input_line("照合済みにする項目のIDを入力してください(空欄で中断)");
+        if input.is_empty() {
+            println!("照合を中断しました");
+            break;
+        }
+        match input.parse() {
+            Ok(id) if mark_cleared(&mut ledger, &account, id, date) => changed = true,
+            _ => println!("照合していない項目のIDを入力してください: {}", input),
+        }
+    }
+    // 照合済みの印を保存してから、その状態で一致した残高を記録する
+    if changed {
+        services::io::write_to_json(&ledger, &config.ledger_path, config);
+    }
+    if matched {
+        let mut assertions = read_assertions(&config.ledger_path);
+        assertions.retain(|assertion| assertion.account != name || assertion.date != date);
+        assertions.push(Assertion::new(name.to_string(), date, balance));
+        assertions.sort_by(|a, b| (&a.account, a.date).cmp(&(&b.account, b.date)));
+        let message = format!(
+            "Reconcile: {} {} {}",
+            name,
+            date,
+            config.currency.format(balance)
+        );
+        write_assertions(&assertions, config, &message);
+        println!("一致しました。{}時点の残高を記録しました", date);
+    }
+}
+
+pub fn read_assertions(file_path: &str) -> Vec<Assertion> {
+    services::io::read_json_or_default(&services::io::get_sibling_path(
+        file_path,
+        RECONCILIATION_FILE,
+    ))
+}
+
+pub fn write_assertions(assertions: &[Assertion], config: &Config, message: &str) {
+    services::io::write_sidecar(&assertions, RECONCILIATION_FILE, config, message);
+}
+
+// 振替には照合の印がないため、常に照合済みとして扱う
+pub fn get_cleared_balance(
+    account: &Account,
+    data: &[models::Item],
+    transfers: &[Transfer],
+    date: NaiveDate,
+) -> i64 {
+    let data: Vec<models::Item> = data
+        .iter()
+        .filter(|item| item.is_cleared() && item.get_date() <= date)
+        .cloned()
+        .collect();
+    let transfers: Vec<Transfer> = transfers
+        .iter()
+        .filter(|transfer| transfer.get_date() <= date)
+        .cloned()
+        .collect();
+    account.get_balance(&data, &transfers)
+}
+
+pub fn get_uncleared<'a>(
+    account: &Account,
+    data: &'a [models::Item],
+    date: NaiveDate,
+) -> Vec<&'a models::Item> {
+    let mut items: Vec<&models::Item> = data
+        .iter()
+        .filter(|item| account.contains(item))
+        .filter(|item| !item.is_cleared() && item.get_date() <= date)
+        .collect();
+    items.sort_by_key(|item| (item.get_date(), item.get_id()));
+    items
+}
+
+// 照合していない項目のうちidが一致するものを照合済みにする
+pub fn mark_cleared(
+    data: &mut [models::Item],
+    account: &Account,
+    id: u32,
+    date: NaiveDate,
+) -> bool {
+    let Some(index) = data.iter().position(|item| {
+        item.get_id() == id
+            && account.contains(item)
+            && !item.is_cleared()
+            && item.get_date() <= date
+    }) else {
+        return false;
+    };
+    data[index] = data[index].clone().with_cleared(true);
+    true
+}
+
+pub fn find_broken_assertions(
+    assertions: &[Assertion],
+    accounts: &[Account],
+    data: &[models::Item],
+    transfers: &[Transfer],
+) -> Vec<BrokenAssertion> {
+    assertions
+        .iter()
+        .filter_map(|assertion| {
+            let account = accounts
+                .iter()
+                .find(|account| account.get_name() == assertion.account)?;
+            let actual = get_cleared_balance(account, data, transfers, assertion.date);
+            (actual != assertion.balance).then(|| BrokenAssertion {
+                account: assertion.account.clone(),
+                date: assertion.date,
+                expected: assertion.balance,
+                actual,
+            })
+        })
+        .collect()
+}
+
+// checkから呼ぶ。記録した残高がない場合は家計簿データを読まない
+pub fn check(config: &Config) -> Vec<BrokenAssertion> {
+    let assertions = read_assertions(&config.ledger_path);
+    if assertions.is_empty() {
+        return Vec::new();
+    }
+    let accounts = services::account::read_accounts(&config.ledger_path);
+    let from = services::account::get_first_opening_date(&accounts);
+    let to = assertions.iter().map(|assertion| assertion.date).max();
+    find_broken_assertions(
+        &assertions,
+        &accounts,
+        &services::io::read_data_between(config, from, to),
+        &services::transfer::read_transfers(&config.ledger_path),
+    )
+}
+
+// 家計簿を書き込んだ後に呼ぶ。変更した項目の日付以降に記録した残高が合わなくなっていれば警告する
+pub fn warn_broken_assertions(config: &Config, changed: &[&models::Item]) {
+    for broken in check_edited(config, changed) {
+        println!("[照合] {}", broken.describe(config));
+    }
+}
+
+// 変更した項目の口座で、その日付以降に記録した残高だけを検査する
+pub fn check_edited(config: &Config, changed: &[&models::Item]) -> Vec<BrokenAssertion> {
+    let Some(from) = changed.iter().map(|item| item.get_date()).min() else {
+        return Vec::new();
+    };
+    let accounts = services::account::read_accounts(&config.ledger_path);
+    let assertions: Vec<Assertion> = read_assertions(&config.ledger_path)
+        .into_iter()
+        .filter(|assertion| assertion.date >= from)
+        .filter(|assertion| {
+            accounts
+                .iter()
+                .find(|account| account.get_name() == assertion.account)
+                .is_some_and(|account| changed.iter().any(|item| account.contains(item)))
+        })
+        .collect();
+    if assertions.is_empty() {
+        return Vec::new();
+    }
+    let to = assertions.iter().map(|assertion| assertion.date).max();
+    find_broken_assertions(
+        &assertions,
+        &accounts,
+        &services::io::read_data_between(
+            config,
+            services::account::get_first_opening_date(&accounts),
+            to,
+        ),
+        &services::transfer::read_transfers(&config.ledger_path),
+    )
+}
+
+fn input_line(message: &str) -> String {
+    println!("{}", message);
+    let mut line = String::new();
+    io::stdin()
+        .read_line(&mut line)
+        .expect("入力に失敗しました");
+    line.trim().to_string()
+}
+
+#[cfg(test)]
+mod reconcile_test {
+    use super::*;
+    use crate::services::account::AccountKind;
+    use std::fs;
+
+    fn get_date(date: &str) -> NaiveDate {
+        NaiveDate::from_str(date).unwrap()
+    }
+
+    fn get_test_data() -> Vec<models::Item> {
+        let salary = models::Category::Income(models::IncomeCategory::Salary);
+        let food = models::Category::Expense(models::ExpenseCategory::Food);
+        let bank = Some("普通預金".to_string());
+        vec![
+            models::Item::new("給料".to_string(), salary, 300000, get_date("2025-08-25"))
+                .with_id(1)
+                .with_account(bank.clone())
+                .with_cleared(true),
+            models::Item::new("引き落とし".to_string(), food, 5000, get_date("2025-08-27"))
+                .with_id(2)
+                .with_account(bank.clone()),
+            models::Item::new("翌月".to_string(), food, 3000, get_date("2025-09-02"))
+                .with_id(3)
+                .with_account(bank),
+            models::Item::new("現金".to_string(), food, 700, get_date("2025-08-20")).with_id(4),
+        ]
+    }
+
+    fn get_test_transfers() -> Vec<Transfer> {
+        vec![Transfer::new(
+            1,
+            get_date("2025-08-05"),
+            "普通預金".to_string(),
+            "現金".to_string(),
+            30000,
+        )]
+    }
+
+    fn get_bank() -> Account {
+        Account::new("普通預金".to_string(), AccountKind::Bank, 100000, None)
+    }
+
+    #[test]
+    fn test_get_cleared_balance() {
+        let data = get_test_data();
+        let date = get_date("2025-08-31");
+        let balance = get_cleared_balance(&get_bank(), &data, &get_test_transfers(), date);
+        assert_eq!(balance, 370000);
+        let uncleared = get_uncleared(&get_bank(), &data, date);
+        assert_eq!(uncleared.len(), 1);
+        assert_eq!(uncleared[0].get_id(), 2);
+    }
+
+    #[test]
+    fn test_mark_cleared() {
+        let mut data = get_test_data();
+        let date = get_date("2025-08-31");
+        // 照合済み・他の口座・照合日より後の項目は対象外
+        assert!(!mark_cleared(&mut data, &get_bank(), 1, date));
+        assert!(!mark_cleared(&mut data, &get_bank(), 3, date));
+        assert!(!mark_cleared(&mut data, &get_bank(), 4, date));
+        assert!(mark_cleared(&mut data, &get_bank(), 2, date));
+        assert!(data[1].is_cleared());
+        assert_eq!(
+            get_cleared_balance(&get_bank(), &data, &get_test_transfers(), date),
+            365000
+        );
+    }
+
+    #[test]
+    fn test_find_broken_assertions() {
+        let assertions = vec![
+            Assertion::new("普通預金".to_string(), get_date("2025-08-31"), 370000),
+            Assertion::new("普通預金".to_string(), get_date("2025-08-10"), 70000),
+        ];
+        let accounts = vec![get_bank()];
+        let transfers = get_test_transfers();
+        let data = get_test_data();
+        assert!(find_broken_assertions(&assertions, &accounts, &data, &transfers).is_empty());
+
+        // 照合済みの項目を後から削除すると不一致になる
+        let mut data = get_test_data();
+        data.remove(0);
+        let broken = find_broken_assertions(&assertions, &accounts, &data, &transfers);
+        assert_eq!(
+            broken,
+            vec![BrokenAssertion {
+                account: "普通預金".to_string(),
+                date: get_date("2025-08-31"),
+                expected: 370000,
+                actual: 70000,
+            }]
+        );
+        assert_eq!(
+            broken[0].describe(&Config::default()),
+            "普通預金の2025-08-31時点の残高が照合時と一致しません: 照合時370000円 現在70000円(差額-300000円)"
+        );
+    }
+
+    #[test]
+    fn test_read_write_assertions() {
+        let test_dir = std::env::temp_dir().join("kakeibo_reconcile_test");
+        fs::create_dir_all(&test_dir).unwrap();
+        let ledger_path = test_dir.join("data.json");
+        let mut config = Config {
+            ledger_path: ledger_path.to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+        config.backup.enabled = false;
+        let ledger_path = config.ledger_path.as_str();
+        assert!(read_assertions(ledger_path).is_empty());
+
+        let assertions = vec![Assertion::new(
+            "普通預金".to_string(),
+            get_date("2025-08-31"),
+            370000,
+        )];
+        write_assertions(&assertions, &config, "Reconcile");
+        assert_eq!(read_assertions(ledger_path), assertions);
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_edited() {
+        let test_dir = std::env::temp_dir().join("kakeibo_reconcile_edited_test");
+        let _ = fs::remove_dir_all(&test_dir);
+        fs::create_dir_all(&test_dir).unwrap();
+        let mut config = Config {
+            ledger_path: test_dir.join("data.json").to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+        config.backup.enabled = false;
+        services::account::write_accounts(&[get_bank()], &config, "Add account");
+        services::transfer::write_transfers(&get_test_transfers(), &config, "Transfer");
+        let assertions = vec![Assertion::new(
+            "普通預金".to_string(),
+            get_date("2025-08-31"),
+            370000,
+        )];
+        write_assertions(&assertions, &config, "Reconcile");
+
+        // 照合済みの給料を削除すると、それ以降に記録した残高と合わなくなる
+        let data = get_test_data();
+        let mut edited = get_test_data();
+        edited.remove(0);
+        services::io::write_to_json(&edited, &config.ledger_path, &config);
+        let broken = check_edited(&config, &[&data[0]]);
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].actual, 70000);
+        // 照合日より後の項目や口座のない項目の変更では検査しない
+        assert!(check_edited(&config, &[&data[2]]).is_empty());
+        assert!(check_edited(&config, &[&data[3]]).is_empty());
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+}
@@ -0,0 +1,537 @@
+use crate::config::Config;
+use crate::models::Category;
+use crate::{models, services};
+use chrono::{Datelike, Days, Local, Months, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+pub const RECURRING_FILE: &str = "recurring.json";
+
+// 定期的に発生する収支のテンプレート。last_appliedまでの分は登録済み
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RecurringTemplate {
+    id: u32,
+    name: String,
+    category: Category,
+    price: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    payment_method: Option<String>,
+    schedule: Schedule,
+    start: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    end: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    exceptions: Vec<Exception>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_applied: Option<NaiveDate>,
+}
+
+// 月の日がその月にない場合(31日など)は月末にする。営業日は土日を除いた日
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Schedule {
+    Monthly { day: u32 },
+    Weekly { weekday: Weekday },
+    Yearly { month: u32, day: u32 },
+    LastBusinessDay,
+}
+
+// 1回分だけの変更。skipでその回を登録せず、priceでその回の金額を変える
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Exception {
+    date: NaiveDate,
+    #[serde(default)]
+    skip: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    price: Option<u32>,
+}
+
+impl Schedule {
+    // "monthly:25", "weekly:mon", "yearly:04-01", "last-business-day"
+    pub fn from_name(name: &str) -> Option<Self> {
+        let (kind, value) = name.split_once(':').unwrap_or((name, ""));
+        match kind {
+            "monthly" => value
+                .parse()
+                .ok()
+                .filter(|day| (1..=31).contains(day))
+                .map(|day| Schedule::Monthly { day }),
+            "weekly" => Weekday::from_str(value)
+                .ok()
+                .map(|weekday| Schedule::Weekly { weekday }),
+            "yearly" => {
+                let (month, day) = value.split_once('-')?;
+                let (month, day) = (month.parse().ok()?, day.parse().ok()?);
+                // 2月29日も指定できるよう、うるう年で日付を検証する
+                NaiveDate::from_ymd_opt(2024, month, day)?;
+                Some(Schedule::Yearly { month, day })
+            }
+            "last-business-day" if value.is_empty() => Some(Schedule::LastBusinessDay),
+            _ => None,
+        }
+    }
+
+    pub fn matches(&self, date: NaiveDate) -> bool {
+        match *self {
+            Schedule::Monthly { day } => date.day() == day.min(get_last_day(date).day()),
+            Schedule::Weekly { weekday } => date.weekday() == weekday,
+            Schedule::Yearly { month, day } => {
+                date.month() == month && date.day() == day.min(get_last_day(date).day())
+            }
+            Schedule::LastBusinessDay => date == get_last_business_day(date),
+        }
+    }
+
+    pub fn get_label(&self) -> String {
+        match self {
+            Schedule::Monthly { day } => format!("毎月{}日", day),
+            Schedule::Weekly { weekday } => format!("毎週{}", get_weekday_label(*weekday)),
+            Schedule::Yearly { month, day } => format!("毎年{}月{}日", month, day),
+            Schedule::LastBusinessDay => "毎月最終営業日".to_string(),
+        }
+    }
+}
+
+impl RecurringTemplate {
+    pub fn new(id: u32, item: &models::Item, schedule: Schedule, start: NaiveDate) -> Self {
+        RecurringTemplate {
+            id,
+            name: item.get_name().to_string(),
+            category: *item.get_item_category(),
+            price: item.get_price(),
+            payment_method: item.get_payment_method().map(|method| method.to_string()),
+            schedule,
+            start,
+            end: None,
+            exceptions: Vec::new(),
+            last_applied: None,
+        }
+    }
+
+    pub fn with_end(mut self, end: Option<NaiveDate>) -> Self {
+        self.end = end;
+        self
+    }
+
+    // fromからtoまで(両端を含む)の発生日。開始日より前と終了日より後は含めない
+    pub fn get_occurrences(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let from = from.max(self.start);
+        let to = self.end.map_or(to, |end| to.min(end));
+        from.iter_days()
+            .take_while(|date| *date <= to)
+            .filter(|date| self.schedule.matches(*date))
+            .collect()
+    }
+
+    pub fn get_next_date(&self, today: NaiveDate) -> Option<NaiveDate> {
+        let from = self.get_pending_start().max(today);
+        self.get_occurrences(from, from + Months::new(13))
+            .first()
+            .copied()
+    }
+
+    // 未登録の最初の日
+    fn get_pending_start(&self) -> NaiveDate {
+        self.last_applied
+            .map_or(self.start, |date| date + Days::new(1))
+    }
+
+    // todayまでの未登録分の項目(IDは未設定)を作り、登録済みの日付を進める
+    pub fn apply(&mut self, today: NaiveDate) -> Vec<models::Item> {
+        let items = self
+            .get_occurrences(self.get_pending_start(), today)
+            .into_iter()
+            .filter_map(|date| {
+                let exception = self
+                    .exceptions
+                    .iter()
+                    .find(|exception| exception.date == date);
+                if exception.is_some_and(|exception| exception.skip) {
+                    return None;
+                }
+                let price = exception
+                    .and_then(|exception| exception.price)
+                    .unwrap_or(self.price);
+                Some(
+                    models::Item::new(self.name.clone(), self.category, price, date)
+                        .with_payment_method(self.payment_method.clone()),
+                )
+            })
+            .collect();
+        if self.get_pending_start() <= today {
+            self.last_applied = Some(self.end.map_or(today, |end| today.min(end)));
+        }
+        items
+    }
+
+    // 同じ日の変更は上書きする
+    pub fn set_exception(&mut self, date: NaiveDate, skip: bool, price: Option<u32>) {
+        self.exceptions.retain(|exception| exception.date != date);
+        self.exceptions.push(Exception { date, skip, price });
+        self.exceptions.sort_by_key(|exception| exception.date);
+    }
+}
+
+pub fn run_list(config: &Config) {
+    let templates = read_templates(&config.ledger_path);
+    if templates.is_empty() {
+        println!("定期的な項目が登録されていません");
+        return;
+    }
+    let today = Local::now().date_naive();
+    for template in templates {
+        let next = template
+            .get_next_date(today)
+            .map_or("なし".to_string(), |date| date.to_string());
+        let end = template
+            .end
+            .map_or(String::new(), |end| format!(" 終了日: {}", end));
+        println!(
+            "ID{} {} {} {} {} 次回: {}{}",
+            template.id,
+            template.name,
+            template.category.get_label(),
+            config.currency.format(template.price as i64),
+            template.schedule.get_label(),
+            next,
+            end
+        );
+    }
+    // コマンドでは自動で登録しないため、登録していない分があれば案内する
+    let due = count_due(&read_templates(&config.ledger_path), today);
+    if due > 0 {
+        println!(
+            "期日が来た未登録の項目が{}件あります。対話モードの起動時か`kakeibo recurring apply`で登録されます",
+            due
+        );
+    }
+}
+
+pub fn run_add(
+    config: &Config,
+    name: &str,
+    amount: &str,
+    category: Option<&str>,
+    schedule: &str,
+    start: Option<&str>,
+    end: Option<&str>,
+) {
+    let amount: i64 = amount.parse().expect("金額は数値で入力してください");
+    let schedule = Schedule::from_name(schedule)
+        .unwrap_or_else(|| panic!("--scheduleの値が不正です: {}", schedule));
+    let start = start.map_or(Local::now().date_naive(), parse_date);
+    let item = services::import::new_item(name, amount, start, category)
+        .unwrap_or_else(|message| panic!("{}", message));
+    let item = match item.get_item_category() {
+        Category::Expense(_) => item.with_payment_method(config.default_payment_method.clone()),
+        Category::Income(_) => item,
+    };
+
+    let mut templates = read_templates(&config.ledger_path);
+    let id = templates
+        .iter()
+        .map(|template| template.id)
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let template = RecurringTemplate::new(id, &item, schedule, start).with_end(end.map(parse_date));
+    println!(
+        "ID{}として登録しました: {} {} {}",
+        id,
+        template.name,
+        template.category.get_label(),
+        template.schedule.get_label()
+    );
+    let message = format!("Add recurring: {}", template.name);
+    templates.push(template);
+    write_templates(&templates, config, &message);
+}
+
+pub fn run_skip(config: &Config, id: &str, date: &str) {
+    update_exception(config, id, parse_date(date), true, None);
+    println!("{}の分を登録しないようにしました", date);
+}
+
+pub fn run_adjust(config: &Config, id: &str, date: &str, price: &str) {
+    let price: u32 = price.parse().expect("金額は数値で入力してください");
+    update_exception(config, id, parse_date(date), false, Some(price));
+    println!(
+        "{}の分の金額を{}に変更しました",
+        date,
+        config.currency.format(price as i64)
+    );
+}
+
+pub fn run_apply(config: &Config) {
+    if apply_due(config, Local::now().date_naive()) == 0 {
+        println!("登録する定期的な項目はありません");
+    }
+}
+
+// 対話モードの起動時と`recurring apply`で呼ばれ、期日が来た定期的な項目を登録する。登録した件数を返す
+pub fn apply_due(config: &Config, today: NaiveDate) -> usize {
+    let mut templates = read_templates(&config.ledger_path);
+    if templates.is_empty() {
+        return 0;
+    }
+    let before = templates.clone();
+    let mut items: Vec<models::Item> = templates
+        .iter_mut()
+        .flat_map(|template| template.apply(today))
+        .collect();
+    if items.is_empty() {
+        if templates != before {
+            write_templates(&templates, config, "Update recurring: last applied");
+        }
+        return 0;
+    }
+
+    let mut data = services::io::read_data_or_create_new_data(&config.ledger_path);
+    let next_id =
+        services::io::get_next_id(&data).max(services::archive::get_next_id(&config.ledger_path));
+    items.sort_by_key(|item| item.get_date());
+    println!("定期的な項目を{}件登録します", items.len());
+    for (offset, item) in items.iter().enumerate() {
+        println!(
+            "{} {} {}",
+            item.get_date(),
+            item.get_name(),
+            config.currency.format(item.get_price_for_summary() as i64)
+        );
+        data.push(item.clone().with_id(next_id + offset as u32));
+    }
+    // 家計簿の保存に失敗した場合に登録済みとして扱わないよう、last_appliedは後から保存する
+    services::io::write_to_json(&data, &config.ledger_path, config);
+    write_templates(
+        &templates,
+        config,
+        &format!("Apply recurring: {} items", items.len()),
+    );
+    items.len()
+}
+
+// todayまでに期日が来て、まだ登録していない項目の件数
+pub fn count_due(templates: &[RecurringTemplate], today: NaiveDate) -> usize {
+    templates
+        .iter()
+        .map(|template| template.clone().apply(today).len())
+        .sum()
+}
+
+pub fn read_templates(file_path: &str) -> Vec<RecurringTemplate> {
+    services::io::read_json_or_default(&services::io::get_sibling_path(file_path, RECURRING_FILE))
+}
+
+pub fn write_templates(templates: &[RecurringTemplate], config: &Config, message: &str) {
+    services::io::write_sidecar(&templates, RECURRING_FILE, config, message);
+}
+
+fn update_exception(config: &Config, id: &str, date: NaiveDate, skip: bool, price: Option<u32>) {
+    let id: u32 = id.parse().expect("IDは数値で入力してください");
+    let mut templates = read_templates(&config.ledger_path);
+    let template = templates
+        .iter_mut()
+        .find(|template| template.id == id)
+        .unwrap_or_else(|| panic!("ID{}の定期的な項目が存在しません", id));
+    if !template.schedule.matches(date) {
+        panic!(
+            "{}は{}の発生日ではありません",
+            date,
+            template.schedule.get_label()
+        );
+    }
+    template.set_exception(date, skip, price);
+    let message = match (skip, price) {
+        (true, _) => format!("Skip recurring: {} {}", template.name, date),
+        (false, Some(price)) => format!(
+            "Adjust recurring: {} {} {}",
+            template.name,
+            date,
+            config.currency.format(price as i64)
+        ),
+        (false, None) => format!("Update recurring: {} {}", template.name, date),
+    };
+    write_templates(&templates, config, &message);
+}
+
+fn parse_date(date: &str) -> NaiveDate {
+    NaiveDate::from_str(date).expect("日付はyyyy-mm-ddの形式で入力してください")
+}
+
+fn get_last_day(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap() + Months::new(1) - Days::new(1)
+}
+
+fn get_last_business_day(date: NaiveDate) -> NaiveDate {
+    let mut last_day = get_last_day(date);
+    while matches!(last_day.weekday(), Weekday::Sat | Weekday::Sun) {
+        last_day = last_day.pred_opt().unwrap();
+    }
+    last_day
+}
+
+fn get_weekday_label(weekday: Weekday) -> &'static str {
+    [
+        "月曜日",
+        "火曜日",
+        "水曜日",
+        "木曜日",
+        "金曜日",
+        "土曜日",
+        "日曜日",
+    ][weekday.num_days_from_monday() as usize]
+}
+
+#[cfg(test)]
+mod recurring_test {
+    use super::*;
+    use std::fs;
+
+    fn get_date(date: &str) -> NaiveDate {
+        parse_date(date)
+    }
+
+    fn get_test_template(schedule: Schedule, start: &str) -> RecurringTemplate {
+        let item = models::Item::new(
+            "家賃".to_string(),
+            Category::Expense(models::ExpenseCategory::Other),
+            80000,
+            get_date(start),
+        );
+        RecurringTemplate::new(1, &item, schedule, get_date(start))
+    }
+
+    #[test]
+    fn test_schedule_from_name() {
+        assert_eq!(
+            Schedule::from_name("monthly:25"),
+            Some(Schedule::Monthly { day: 25 })
+        );
+        assert_eq!(
+            Schedule::from_name("weekly:mon"),
+            Some(Schedule::Weekly {
+                weekday: Weekday::Mon
+            })
+        );
+        assert_eq!(
+            Schedule::from_name("yearly:02-29"),
+            Some(Schedule::Yearly { month: 2, day: 29 })
+        );
+        assert_eq!(
+            Schedule::from_name("last-business-day"),
+            Some(Schedule::LastBusinessDay)
+        );
+        assert_eq!(Schedule::from_name("monthly:32"), None);
+        assert_eq!(Schedule::from_name("yearly:13-01"), None);
+        assert_eq!(Schedule::from_name("daily"), None);
+    }
+
+    #[test]
+    fn test_get_occurrences() {
+        let template = get_test_template(Schedule::Monthly { day: 31 }, "2025-01-15");
+        assert_eq!(
+            template.get_occurrences(get_date("2025-01-01"), get_date("2025-04-30")),
+            vec![
+                get_date("2025-01-31"),
+                get_date("2025-02-28"),
+                get_date("2025-03-31"),
+                get_date("2025-04-30"),
+            ]
+        );
+
+        // 2025-08-31は日曜日、2025-05-31は土曜日
+        let template = get_test_template(Schedule::LastBusinessDay, "2025-05-01")
+            .with_end(Some(get_date("2025-08-31")));
+        assert_eq!(
+            template.get_occurrences(get_date("2025-05-01"), get_date("2025-12-31")),
+            vec![
+                get_date("2025-05-30"),
+                get_date("2025-06-30"),
+                get_date("2025-07-31"),
+                get_date("2025-08-29"),
+            ]
+        );
+
+        let template = get_test_template(Schedule::Yearly { month: 2, day: 29 }, "2024-01-01");
+        assert_eq!(
+            template.get_occurrences(get_date("2024-01-01"), get_date("2025-12-31")),
+            vec![get_date("2024-02-29"), get_date("2025-02-28")]
+        );
+
+        let template = get_test_template(
+            Schedule::Weekly {
+                weekday: Weekday::Fri,
+            },
+            "2025-08-01",
+        );
+        assert_eq!(
+            template.get_occurrences(get_date("2025-07-01"), get_date("2025-08-10")),
+            vec![get_date("2025-08-01"), get_date("2025-08-08")]
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut template = get_test_template(Schedule::Monthly { day: 27 }, "2025-06-01");
+        template.set_exception(get_date("2025-07-27"), true, None);
+        template.set_exception(get_date("2025-08-27"), false, Some(85000));
+
+        let items = template.apply(get_date("2025-08-31"));
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].get_date(), get_date("2025-06-27"));
+        assert_eq!(items[1].get_price(), 85000);
+        assert_eq!(template.last_applied, Some(get_date("2025-08-31")));
+
+        // 登録済みの期間は再度登録しない
+        assert_eq!(count_due(&[template.clone()], get_date("2025-09-27")), 1);
+        assert!(template.apply(get_date("2025-09-26")).is_empty());
+        assert_eq!(template.apply(get_date("2025-09-27")).len(), 1);
+        assert_eq!(
+            template.get_next_date(get_date("2025-09-28")),
+            Some(get_date("2025-10-27"))
+        );
+    }
+
+    #[test]
+    fn test_apply_due() {
+        let test_dir = std::env::temp_dir().join("kakeibo_recurring_test");
+        fs::create_dir_all(&test_dir).unwrap();
+        let ledger_path = test_dir.join("data.json");
+        let mut config = Config {
+            ledger_path: ledger_path.to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+        config.backup.enabled = false;
+        services::io::write_to_json(
+            &[models::Item::new(
+                "既存".to_string(),
+                Category::Expense(models::ExpenseCategory::Food),
+                1000,
+                get_date("2025-06-01"),
+            )
+            .with_id(1)],
+            &config.ledger_path,
+            &config,
+        );
+        write_templates(
+            &[get_test_template(
+                Schedule::Monthly { day: 27 },
+                "2025-07-01",
+            )],
+            &config,
+            "Add recurring",
+        );
+
+        assert_eq!(apply_due(&config, get_date("2025-08-31")), 2);
+        let data = services::io::read_data(&config.ledger_path);
+        assert_eq!(data.len(), 3);
+        assert_eq!(data.last().unwrap().get_id(), 3);
+        assert_eq!(apply_due(&config, get_date("2025-08-31")), 0);
+        assert_eq!(
+            read_templates(&config.ledger_path)[0].last_applied,
+            Some(get_date("2025-08-31"))
+        );
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+}
@@ -0,0 +1,371 @@
+use super::MonthlyTotal;
+use crate::config::Config;
+use crate::{models, services};
+use chrono::{Datelike, Local};
+use std::f64::consts::PI;
+
+const INCOME_COLOR: &str = "#4caf50";
+const EXPENSE_COLOR: &str = "#e57373";
+const BALANCE_COLOR: &str = "#1e88e5";
+const PALETTE: [&str; 6] = [
+    "#ef5350", "#ffa726", "#66bb6a", "#42a5f5", "#ab47bc", "#8d6e63",
+];
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:960px;margin:2em auto;padding:0 1em;color:#333}\
+h1{font-size:1.6em}h2{font-size:1.2em;border-bottom:1px solid #ccc;padding-bottom:.2em;margin-top:2em}\
+table{border-collapse:collapse;margin:1em 0}th,td{border:1px solid #ddd;padding:.3em .8em}\
+td.number{text-align:right}.negative{color:#c62828}svg{max-width:100%;height:auto}\
+.legend span{display:inline-block;width:.8em;height:.8em;margin:0 .3em 0 1em}";
+
+// 外部のスクリプトやCDNを使わず、グラフはインラインのSVGで描く1ファイルのHTML
+pub fn render(data: &[models::Item], config: &Config) -> String {
+    let totals = super::get_monthly_totals(data);
+    let income: u32 = totals.iter().map(|total| total.income).sum();
+    let expense: u32 = totals.iter().map(|total| total.expense).sum();
+    let format = |price: i64| config.currency.format(price);
+
+    let mut body = vec![
+        "<h1>家計簿レポート</h1>".to_string(),
+        format!("<p>作成日: {}</p>", Local::now().date_naive()),
+        "<table>".to_string(),
+        format!(
+            "<tr><th>収入</th><td class=\"number\">{}</td></tr>",
+            format(income as i64)
+        ),
+        format!(
+            "<tr><th>支出</th><td class=\"number\">{}</td></tr>",
+            format(expense as i64)
+        ),
+        format!(
+            "<tr><th>収支</th>{}</tr>",
+            render_price_cell(income as i32 - expense as i32, config)
+        ),
+        "</table>".to_string(),
+        "<h2>月ごとの収入と支出</h2>".to_string(),
+        render_bar_chart(&totals, config),
+        "<h2>支出のカテゴリー別内訳</h2>".to_string(),
+        render_pie_chart(&super::get_category_totals(data), config),
+        "<h2>累計の収支</h2>".to_string(),
+        render_line_chart(&totals, config),
+        "<h2>月ごとの収支</h2>".to_string(),
+        "<table><tr><th>年月</th><th>収入</th><th>支出</th><th>収支</th></tr>".to_string(),
+    ];
+    for total in &totals {
+        body.push(format!(
+            "<tr><td>{}/{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td>{}</tr>",
+            total.month.year(),
+            total.month.month(),
+            format(total.income as i64),
+            format(total.expense as i64),
+            render_price_cell(total.get_balance(), config)
+        ));
+    }
+    body.push("</table>".to_string());
+
+    body.push("<h2>年ごとの統計</h2>".to_string());
+    body.push("<table><tr><th>期間</th><th>収支</th></tr>".to_string());
+    for (year, price) in services::statistics::get_yearly_statistics(data, config) {
+        body.push(format!(
+            "<tr><td>{}</td>{}</tr>",
+            config.get_year_label(year),
+            render_price_cell(price, config)
+        ));
+    }
+    body.push("</table>".to_string());
+
+    body.push("<h2>月ごとの統計</h2>".to_string());
+    body.push("<table><tr><th>月</th><th>収支</th></tr>".to_string());
+    for (month, price) in services::statistics::get_monthly_statistics(data) {
+        body.push(format!(
+            "<tr><td>{}月</td>{}</tr>",
+            month,
+            render_price_cell(price, config)
+        ));
+    }
+    body.push("</table>".to_string());
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n<title>家計簿レポート</title>\n<style>{}</style>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+        STYLE,
+        body.join("\n")
+    )
+}
+
+fn render_price_cell(price: i32, config: &Config) -> String {
+    let class = if price < 0 {
+        "number negative"
+    } else {
+        "number"
+    };
+    format!(
+        "<td class=\"{}\">{}</td>",
+        class,
+        escape(&config.currency.format(price as i64))
+    )
+}
+
+// 月ごとに収入と支出の棒を並べる
+fn render_bar_chart(totals: &[MonthlyTotal], config: &Config) -> String {
+    let (width, height, left, bottom) = (720.0, 280.0, 90.0, 30.0);
+    let plot_width = width - left - 10.0;
+    let plot_height = height - bottom - 10.0;
+    let max = totals
+        .iter()
+        .map(|total| total.income.max(total.expense))
+        .max()
+        .unwrap_or(0)
+        .max(1) as f64;
+    let mut svg = vec![
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" role=\"img\">",
+            width, height
+        ),
+        render_axis_label(left, 10.0, &config.currency.format(max as i64)),
+        render_axis_label(left, 10.0 + plot_height, &config.currency.format(0)),
+        format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#999\"/>",
+            left,
+            10.0 + plot_height,
+            width - 10.0,
+            10.0 + plot_height
+        ),
+    ];
+    let group_width = plot_width / totals.len().max(1) as f64;
+    let bar_width = group_width * 0.35;
+    for (index, total) in totals.iter().enumerate() {
+        let x = left + group_width * index as f64 + group_width * 0.15;
+        for (offset, price, color) in [
+            (0.0, total.income, INCOME_COLOR),
+            (bar_width, total.expense, EXPENSE_COLOR),
+        ] {
+            let bar_height = plot_height * price as f64 / max;
+            svg.push(format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"><title>{}</title></rect>",
+                x + offset,
+                10.0 + plot_height - bar_height,
+                bar_width,
+                bar_height,
+                color,
+                escape(&config.currency.format(price as i64))
+            ));
+        }
+        svg.push(format!(
+            "<text x=\"{:.1}\" y=\"{}\" font-size=\"11\" text-anchor=\"middle\">{}/{}</text>",
+            x + bar_width,
+            height - 10.0,
+            total.month.year() % 100,
+            total.month.month()
+        ));
+    }
+    svg.push("</svg>".to_string());
+    svg.push(render_legend(&[
+        ("収入", INCOME_COLOR),
+        ("支出", EXPENSE_COLOR),
+    ]));
+    svg.join("\n")
+}
+
+// 支出のカテゴリーごとの割合
+fn render_pie_chart(
+    totals: &std::collections::BTreeMap<models::Category, u32>,
+    config: &Config,
+) -> String {
+    let expenses: Vec<(&models::Category, u32)> = totals
+        .iter()
+        .filter(|(category, price)| matches!(category, models::Category::Expense(_)) && **price > 0)
+        .map(|(category, price)| (category, *price))
+        .collect();
+    let sum: u32 = expenses.iter().map(|(_, price)| price).sum();
+    if sum == 0 {
+        return "<p>支出がありません</p>".to_string();
+    }
+    let (cx, cy, r) = (120.0, 120.0, 100.0);
+    let mut svg = vec!["<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 240 240\" width=\"240\" role=\"img\">".to_string()];
+    let mut legend = Vec::new();
+    let mut start = 0.0;
+    for (index, (category, price)) in expenses.iter().enumerate() {
+        let color = PALETTE[index % PALETTE.len()];
+        let ratio = *price as f64 / sum as f64;
+        let title = format!(
+            "{} {} ({:.1}%)",
+            category.get_label(),
+            config.currency.format(*price as i64),
+            ratio * 100.0
+        );
+        if expenses.len() == 1 {
+            svg.push(format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"><title>{}</title></circle>",
+                cx,
+                cy,
+                r,
+                color,
+                escape(&title)
+            ));
+        } else {
+            let end = start + ratio * 2.0 * PI;
+            let point = |angle: f64| (cx + r * angle.sin(), cy - r * angle.cos());
+            let (x1, y1) = point(start);
+            let (x2, y2) = point(end);
+            svg.push(format!(
+                "<path d=\"M{} {} L{:.2} {:.2} A{} {} 0 {} 1 {:.2} {:.2} Z\" fill=\"{}\"><title>{}</title></path>",
+                cx,
+                cy,
+                x1,
+                y1,
+                r,
+                r,
+                if ratio > 0.5 { 1 } else { 0 },
+                x2,
+                y2,
+                color,
+                escape(&title)
+            ));
+            start = end;
+        }
+        legend.push((title, color));
+    }
+    svg.push("</svg>".to_string());
+    let legend: Vec<(&str, &str)> = legend
+        .iter()
+        .map(|(title, color)| (title.as_str(), *color))
+        .collect();
+    svg.push(render_legend(&legend));
+    svg.join("\n")
+}
+
+// 最初の月からの累計の収支の推移
+fn render_line_chart(totals: &[MonthlyTotal], config: &Config) -> String {
+    let (width, height, left) = (720.0, 240.0, 90.0);
+    let plot_width = width - left - 10.0;
+    let plot_height = height - 20.0;
+    let balances: Vec<i64> = totals
+        .iter()
+        .scan(0i64, |balance, total| {
+            *balance += total.get_balance() as i64;
+            Some(*balance)
+        })
+        .collect();
+    let max = balances.iter().copied().max().unwrap_or(0).max(0);
+    let min = balances.iter().copied().min().unwrap_or(0).min(0);
+    let range = (max - min).max(1) as f64;
+    let y = |balance: i64| 10.0 + plot_height * (max - balance) as f64 / range;
+    let step = plot_width / (balances.len().max(2) - 1) as f64;
+    let points: Vec<String> = balances
+        .iter()
+        .enumerate()
+        .map(|(index, balance)| format!("{:.1},{:.1}", left + step * index as f64, y(*balance)))
+        .collect();
+    [
+        format!("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" role=\"img\">", width, height),
+        render_axis_label(left, 10.0, &config.currency.format(max)),
+        render_axis_label(left, 10.0 + plot_height, &config.currency.format(min)),
+        format!(
+            "<line x1=\"{}\" y1=\"{:.1}\" x2=\"{}\" y2=\"{:.1}\" stroke=\"#999\" stroke-dasharray=\"4\"/>",
+            left,
+            y(0),
+            width - 10.0,
+            y(0)
+        ),
+        format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>",
+            points.join(" "),
+            BALANCE_COLOR
+        ),
+        "</svg>".to_string(),
+    ]
+    .join("\n")
+}
+
+fn render_axis_label(x: f64, y: f64, label: &str) -> String {
+    format!(
+        "<text x=\"{}\" y=\"{}\" font-size=\"11\" text-anchor=\"end\" dominant-baseline=\"middle\">{}</text>",
+        x - 5.0,
+        y,
+        escape(label)
+    )
+}
+
+fn render_legend(entries: &[(&str, &str)]) -> String {
+    let entries: Vec<String> = entries
+        .iter()
+        .map(|(label, color)| {
+            format!(
+                "<span style=\"background:{}\"></span>{}",
+                color,
+                escape(label)
+            )
+        })
+        .collect();
+    format!("<p class=\"legend\">{}</p>", entries.join(""))
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod html_test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn get_test_data() -> Vec<models::Item> {
+        vec![
+            models::Item::new(
+                "給料".to_string(),
+                models::Category::Income(models::IncomeCategory::Salary),
+                300000,
+                NaiveDate::from_ymd_opt(2025, 7, 25).unwrap(),
+            ),
+            models::Item::new(
+                "ランチ".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Food),
+                800,
+                NaiveDate::from_ymd_opt(2025, 8, 1).unwrap(),
+            ),
+            models::Item::new(
+                "本".to_string(),
+                models::Category::Expense(models::ExpenseCategory::Hobby),
+                1500,
+                NaiveDate::from_ymd_opt(2025, 8, 3).unwrap(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_render() {
+        let html = render(&get_test_data(), &Config::default());
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(!html.contains("<script"));
+        // 外部のリソースを読み込まない
+        assert!(!html.contains("https://"));
+        assert!(!html.contains("src="));
+        assert_eq!(html.matches("<svg").count(), 3);
+        assert!(html.contains("<td>2025/8</td>"));
+        assert!(html.contains("<td>2025年</td><td class=\"number\">297700円</td>"));
+        assert!(html.contains("<td class=\"number negative\">-2300円</td>"));
+    }
+
+    #[test]
+    fn test_render_pie_chart() {
+        let config = Config::default();
+        let data = get_test_data();
+        let pie = render_pie_chart(&super::super::get_category_totals(&data), &config);
+        assert_eq!(pie.matches("<path").count(), 2);
+        assert!(pie.contains("食費 800円 (34.8%)"));
+
+        let pie = render_pie_chart(&super::super::get_category_totals(&data[..2]), &config);
+        assert!(pie.contains("<circle"));
+
+        let pie = render_pie_chart(&super::super::get_category_totals(&data[..1]), &config);
+        assert_eq!(pie, "<p>支出がありません</p>");
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("<A&B>\""), "&lt;A&amp;B&gt;&quot;");
+    }
+}
@@ -0,0 +1,230 @@
+use crate::config::Config;
+use crate::services::budget::BudgetStatus;
+use crate::{models, services};
+use chrono::{Datelike, Months, NaiveDate};
+use std::collections::{BTreeMap, BTreeSet};
+
+const TOP_EXPENSE_COUNT: usize = 5;
+
+// 指定した月の収支・カテゴリー別の支出・大きな支出を前月と比べ、予算の消化状況を添えたMarkdown
+pub fn render(
+    data: &[models::Item],
+    config: &Config,
+    month: NaiveDate,
+    budgets: &[BudgetStatus],
+) -> String {
+    let previous_month = month - Months::new(1);
+    let category_table = services::summarize::get_category_table(data);
+    let empty = BTreeMap::new();
+    let current = category_table.get(&month).unwrap_or(&empty);
+    let previous = category_table.get(&previous_month).unwrap_or(&empty);
+    let format = |price: i64| config.currency.format(price);
+    let format_diff = |price: i64| services::summarize::format_price(price, &config.currency);
+
+    let mut lines = vec![
+        format!("# {}年{}月の家計簿", month.year(), month.month()),
+        String::new(),
+        "## 収支".to_string(),
+        String::new(),
+        format!(
+            "| | {}/{} | 前月({}/{}) | 増減 |",
+            month.year(),
+            month.month(),
+            previous_month.year(),
+            previous_month.month()
+        ),
+        "| --- | ---: | ---: | ---: |".to_string(),
+    ];
+    let (income, expense) = get_totals(current);
+    let (previous_income, previous_expense) = get_totals(previous);
+    for (label, price, previous_price) in [
+        ("収入", income, previous_income),
+        ("支出", expense, previous_expense),
+        ("収支", income - expense, previous_income - previous_expense),
+    ] {
+        lines.push(format!(
+            "| {} | {} | {} | {} |",
+            label,
+            format(price),
+            format(previous_price),
+            format_diff(price - previous_price)
+        ));
+    }
+
+    lines.extend([
+        String::new(),
+        "## カテゴリー別の支出".to_string(),
+        String::new(),
+        "| カテゴリー | 金額 | 割合 | 前月 | 増減 |".to_string(),
+        "| --- | ---: | ---: | ---: | ---: |".to_string(),
+    ]);
+    let categories: BTreeSet<&models::Category> = current
+        .keys()
+        .chain(previous.keys())
+        .filter(|category| matches!(category, models::Category::Expense(_)))
+        .collect();
+    for category in categories {
+        let price = current.get(category).copied().unwrap_or(0) as i64;
+        let previous_price = previous.get(category).copied().unwrap_or(0) as i64;
+        let ratio = if expense > 0 {
+            price as f64 * 100.0 / expense as f64
+        } else {
+            0.0
+        };
+        lines.push(format!(
+            "| {} | {} | {:.1}% | {} | {} |",
+            category.get_label(),
+            format(price),
+            ratio,
+            format(previous_price),
+            format_diff(price - previous_price)
+        ));
+    }
+
+    lines.extend([
+        String::new(),
+        "## 大きな支出".to_string(),
+        String::new(),
+        "| 日付 | 品目 | カテゴリー | 金額 |".to_string(),
+        "| --- | --- | --- | ---: |".to_string(),
+    ]);
+    let mut expenses: Vec<&models::Item> = data
+        .iter()
+        .filter(|item| item.get_first_day() == month)
+        .filter(|item| matches!(item.get_item_category(), models::Category::Expense(_)))
+        .collect();
+    expenses.sort_by_key(|item| std::cmp::Reverse(item.get_price()));
+    for item in expenses.iter().take(TOP_EXPENSE_COUNT) {
+        lines.push(format!(
+            "| {} | {} | {} | {} |",
+            item.get_date(),
+            escape(item.get_name()),
+            item.get_item_category().get_label(),
+            format(item.get_price() as i64)
+        ));
+    }
+
+    // 予算を設定していない場合も節は残し、設定方法を案内する
+    lines.extend([String::new(), "## 予算".to_string(), String::new()]);
+    if budgets.is_empty() {
+        lines.push(
+            "予算が設定されていません(`kakeibo budget set <カテゴリー> <金額>` で設定できます)"
+                .to_string(),
+        );
+    } else {
+        lines.extend([
+            "| カテゴリー | 予算 | 支出 | 残り | 消化率 | 月末見込み |".to_string(),
+            "| --- | ---: | ---: | ---: | ---: | ---: |".to_string(),
+        ]);
+        for status in budgets {
+            lines.push(format!(
+                "| {} | {} | {} | {} | {:.1}% | {} |",
+                status.label,
+                format(status.budget as i64),
+                format(status.spent as i64),
+                format(status.remaining),
+                status.percentage,
+                format(status.projected as i64)
+            ));
+        }
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+// "2025-08" を月初の日付にする
+pub fn parse_month(value: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(&format!("{}-01", value), "%Y-%m-%d")
+        .unwrap_or_else(|_| panic!("月はyyyy-mmの形式で指定してください: {}", value))
+}
+
+fn get_totals(prices: &BTreeMap<models::Category, u32>) -> (i64, i64) {
+    prices.iter().fold(
+        (0, 0),
+        |(income, expense), (category, price)| match category {
+            models::Category::Income(_) => (income + *price as i64, expense),
+            models::Category::Expense(_) => (income, expense + *price as i64),
+        },
+    )
+}
+
+// 表の区切りと解釈されないようにする
+fn escape(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod markdown_test {
+    use super::*;
+
+    fn get_test_item(
+        name: &str,
+        category: models::Category,
+        price: u32,
+        date: &str,
+    ) -> models::Item {
+        models::Item::new(
+            name.to_string(),
+            category,
+            price,
+            NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+        )
+    }
+
+    fn get_test_data() -> Vec<models::Item> {
+        let salary = models::Category::Income(models::IncomeCategory::Salary);
+        let food = models::Category::Expense(models::ExpenseCategory::Food);
+        let hobby = models::Category::Expense(models::ExpenseCategory::Hobby);
+        vec![
+            get_test_item("給料", salary, 300000, "2025-07-25"),
+            get_test_item("スーパー", food, 40000, "2025-07-10"),
+            get_test_item("給料", salary, 300000, "2025-08-25"),
+            get_test_item("スーパー", food, 30000, "2025-08-10"),
+            get_test_item("ゲーム|限定版", hobby, 8000, "2025-08-15"),
+            get_test_item("翌月", food, 1000, "2025-09-01"),
+        ]
+    }
+
+    #[test]
+    fn test_render() {
+        let month = parse_month("2025-08");
+        let markdown = render(&get_test_data(), &Config::default(), month, &[]);
+        assert!(markdown.starts_with("# 2025年8月の家計簿\n"));
+        assert!(markdown.contains("| 支出 | 38000円 | 40000円 | -2000円 |"));
+        assert!(markdown.contains("| 収支 | 262000円 | 260000円 | +2000円 |"));
+        assert!(markdown.contains("| 食費 | 30000円 | 78.9% | 40000円 | -10000円 |"));
+        assert!(markdown.contains("| 趣味 | 8000円 | 21.1% | 0円 | +8000円 |"));
+        assert!(markdown.contains(
+            "| 2025-08-10 | スーパー | 食費 | 30000円 |\n| 2025-08-15 | ゲーム\\|限定版 |"
+        ));
+        assert!(!markdown.contains("翌月"));
+        assert!(markdown.ends_with("## 予算\n\n予算が設定されていません(`kakeibo budget set <カテゴリー> <金額>` で設定できます)\n"));
+    }
+
+    #[test]
+    fn test_render_budgets() {
+        let month = parse_month("2025-08");
+        let data = get_test_data();
+        let budgets = services::budget::get_statuses(
+            &data,
+            &BTreeMap::from([(models::ExpenseCategory::Hobby, 5000)]),
+            month,
+            NaiveDate::from_ymd_opt(2025, 8, 31).unwrap(),
+        );
+        let markdown = render(&data, &Config::default(), month, &budgets);
+        assert!(markdown.contains("## 予算"));
+        assert!(markdown.contains("| 趣味 | 5000円 | 8000円 | -3000円 | 160.0% | 8000円 |"));
+    }
+
+    #[test]
+    fn test_render_january() {
+        let markdown = render(&[], &Config::default(), parse_month("2026-01"), &[]);
+        assert!(markdown.contains("| | 2026/1 | 前月(2025/12) | 増減 |"));
+    }
+
+    #[test]
+    #[should_panic(expected = "月はyyyy-mmの形式で指定してください: 2025/08")]
+    fn test_parse_month_invalid() {
+        parse_month("2025/08");
+    }
+}
@@ -0,0 +1,275 @@
+pub mod html;
+pub mod markdown;
+pub mod pdf;
+
+use crate::config::Config;
+use crate::{models, services};
+use chrono::{Local, Months, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// 月ごとの収入・支出。収支はsummarizeの集計と同じになる
+#[derive(Debug, PartialEq)]
+pub struct MonthlyTotal {
+    pub month: NaiveDate,
+    pub income: u32,
+    pub expense: u32,
+}
+
+impl MonthlyTotal {
+    pub fn get_balance(&self) -> i32 {
+        self.income as i32 - self.expense as i32
+    }
+}
+
+// --output jsonで出力する集計結果。periodsは期間ごと、totalsは全期間の合計
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Report {
+    pub periods: Vec<PeriodReport>,
+    pub totals: Totals,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct PeriodReport {
+    pub period: String,
+    pub label: String,
+    #[serde(flatten)]
+    pub totals: Totals,
+}
+
+#[derive(Serialize, Debug, PartialEq, Default)]
+pub struct Totals {
+    pub income: u32,
+    pub expense: u32,
+    pub balance: i32,
+    pub categories: Vec<CategoryTotal>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct CategoryTotal {
+    #[serde(rename = "type")]
+    pub category_type: String,
+    pub name: String,
+    pub label: String,
+    pub amount: u32,
+}
+
+impl Report {
+    // 期間のキーごとに(期間の表記, 項目)をまとめたものから作る
+    pub fn new<K: Ord>(groups: BTreeMap<K, (String, String, Vec<&models::Item>)>) -> Self {
+        let mut all_items = Vec::new();
+        let periods = groups
+            .into_values()
+            .map(|(period, label, items)| {
+                let totals = Totals::new(&items);
+                all_items.extend(items);
+                PeriodReport {
+                    period,
+                    label,
+                    totals,
+                }
+            })
+            .collect();
+        Report {
+            periods,
+            totals: Totals::new(&all_items),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("JSONに変換できませんでした")
+    }
+}
+
+impl Totals {
+    fn new(items: &[&models::Item]) -> Self {
+        let mut prices: BTreeMap<models::Category, u32> = BTreeMap::new();
+        for item in items {
+            for (category, price) in item.get_category_prices() {
+                *prices.entry(category).or_insert(0) += price;
+            }
+        }
+        let mut totals = Totals::default();
+        for (category, amount) in prices {
+            let (category_type, name) = match category {
+                models::Category::Income(category) => {
+                    totals.income += amount;
+                    ("income", format!("{:?}", category))
+                }
+                models::Category::Expense(category) => {
+                    totals.expense += amount;
+                    ("expense", format!("{:?}", category))
+                }
+            };
+            totals.categories.push(CategoryTotal {
+                category_type: category_type.to_string(),
+                name,
+                label: category.get_label().to_string(),
+                amount,
+            });
+        }
+        totals.balance = totals.income as i32 - totals.expense as i32;
+        totals
+    }
+}
+
+// 年度ごとの推移を含むため、アーカイブもすべて読み込む
+pub fn run_html(config: &Config, file_path: &str) {
+    let data = services::io::read_data_with_archives_or_panic(&config.ledger_path);
+    let html = html::render(&data, config);
+    services::export::write_output(html.as_bytes(), Some(file_path));
+}
+
+// 前月との比較のため、前月を含む年度のデータも読み込む
+pub fn run_markdown(config: &Config, month: NaiveDate, file_path: Option<&str>) {
+    let previous_month = month - Months::new(1);
+    let data = services::io::read_data_between(config, Some(previous_month), Some(month));
+    let budgets = services::budget::get_statuses(
+        &data,
+        &services::budget::read_budgets(&config.ledger_path),
+        month,
+        Local::now().date_naive(),
+    );
+    let markdown = markdown::render(&data, config, month, &budgets);
+    services::export::write_output(markdown.as_bytes(), file_path);
+}
+
+// 前年度との比較のため、前年度のアーカイブも読み込む
+pub fn run_pdf(config: &Config, year: i32, file_path: &str) {
+    let data = services::io::read_data_for_years(&config.ledger_path, year - 1, year);
+    let pdf = pdf::render(&data, config, year);
+    services::export::write_output(&pdf, Some(file_path));
+}
+
+pub fn get_monthly_totals(data: &[models::Item]) -> Vec<MonthlyTotal> {
+    services::summarize::get_category_table(data)
+        .into_iter()
+        .map(|(month, prices)| {
+            let (income, expense) = prices.iter().fold(
+                (0, 0),
+                |(income, expense), (category, price)| match category {
+                    models::Category::Income(_) => (income + price, expense),
+                    models::Category::Expense(_) => (income, expense + price),
+                },
+            );
+            MonthlyTotal {
+                month,
+                income,
+                expense,
+            }
+        })
+        .collect()
+}
+
+// 期間全体のカテゴリーごとの金額
+pub fn get_category_totals(data: &[models::Item]) -> BTreeMap<models::Category, u32> {
+    let mut totals = BTreeMap::new();
+    for prices in services::summarize::get_category_table(data).into_values() {
+        for (category, price) in prices {
+            *totals.entry(category).or_insert(0) += price;
+        }
+    }
+    totals
+}
+
+#[cfg(test)]
+mod report_test {
+    use super::*;
+
+    fn get_test_item(category: models::Category, price: u32, month: u32) -> models::Item {
+        models::Item::new(
+            "テスト".to_string(),
+            category,
+            price,
+            NaiveDate::from_ymd_opt(2025, month, 10).unwrap(),
+        )
+    }
+
+    fn get_test_data() -> Vec<models::Item> {
+        let salary = models::Category::Income(models::IncomeCategory::Salary);
+        let food = models::Category::Expense(models::ExpenseCategory::Food);
+        let hobby = models::Category::Expense(models::ExpenseCategory::Hobby);
+        vec![
+            get_test_item(salary, 300000, 7),
+            get_test_item(food, 40000, 7),
+            get_test_item(food, 30000, 8),
+            get_test_item(hobby, 5000, 8),
+        ]
+    }
+
+    #[test]
+    fn test_get_monthly_totals() {
+        let totals = get_monthly_totals(&get_test_data());
+        assert_eq!(
+            totals,
+            vec![
+                MonthlyTotal {
+                    month: NaiveDate::from_ymd_opt(2025, 7, 1).unwrap(),
+                    income: 300000,
+                    expense: 40000,
+                },
+                MonthlyTotal {
+                    month: NaiveDate::from_ymd_opt(2025, 8, 1).unwrap(),
+                    income: 0,
+                    expense: 35000,
+                },
+            ]
+        );
+        assert_eq!(totals[1].get_balance(), -35000);
+    }
+
+    #[test]
+    fn test_report() {
+        let data = get_test_data();
+        let mut groups = BTreeMap::new();
+        for item in &data {
+            groups
+                .entry(item.get_first_day())
+                .or_insert_with(|| (item.get_first_day().to_string(), String::new(), Vec::new()))
+                .2
+                .push(item);
+        }
+        let report = Report::new(groups);
+        assert_eq!(report.periods.len(), 2);
+        assert_eq!(report.periods[1].totals.balance, -35000);
+        assert_eq!(report.totals.income, 300000);
+        assert_eq!(report.totals.expense, 75000);
+        assert_eq!(report.totals.balance, 225000);
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["periods"][0]["period"], "2025-07-01");
+        assert_eq!(json["periods"][0]["income"], 300000);
+        assert_eq!(
+            json["totals"]["categories"][1],
+            serde_json::json!({"type": "expense", "name": "Food", "label": "食費", "amount": 70000})
+        );
+    }
+
+    #[test]
+    fn test_get_category_totals() {
+        let totals = get_category_totals(&get_test_data());
+        assert_eq!(
+            totals[&models::Category::Expense(models::ExpenseCategory::Food)],
+            70000
+        );
+        assert_eq!(totals.len(), 3);
+    }
+
+    #[test]
+    fn test_report_splits() {
+        let food = models::Category::Expense(models::ExpenseCategory::Food);
+        let hobby = models::Category::Expense(models::ExpenseCategory::Hobby);
+        let item = get_test_item(food, 3000, 8).with_splits(vec![
+            models::Split::new(food, 2200),
+            models::Split::new(hobby, 800),
+        ]);
+        let report = Report::new(BTreeMap::from([(
+            item.get_first_day(),
+            (String::new(), String::new(), vec![&item]),
+        )]));
+        assert_eq!(report.totals.expense, 3000);
+        assert_eq!(report.totals.categories[0].amount, 2200);
+        assert_eq!(report.totals.categories[1].amount, 800);
+        assert_eq!(get_category_totals(&[item])[&hobby], 800);
+    }
+}
@@ -0,0 +1,449 @@
+use super::{CategoryTotal, Totals};
+use crate::config::Config;
+use crate::{models, services};
+use chrono::{Datelike, Local, Months, NaiveDate};
+
+// A4縦(pt)
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+const FONT_SIZE: f32 = 10.0;
+const ROW_HEIGHT: f32 = 16.0;
+const TOP_EXPENSE_COUNT: usize = 10;
+
+// フォントは埋め込まず、PDFビューアーが持つ日本語フォント(平成角ゴシック)をUCS-2で指定する
+const FONT_OBJECTS: [&str; 3] = [
+    "<< /Type /Font /Subtype /Type0 /BaseFont /HeiseiKakuGo-W5 /Encoding /UniJIS-UCS2-H /DescendantFonts [4 0 R] >>",
+    "<< /Type /Font /Subtype /CIDFontType0 /BaseFont /HeiseiKakuGo-W5 \
+/CIDSystemInfo << /Registry (Adobe) /Ordering (Japan1) /Supplement 2 >> \
+/FontDescriptor 5 0 R /DW 1000 /W [1 95 500] >>",
+    "<< /Type /FontDescriptor /FontName /HeiseiKakuGo-W5 /Flags 4 /FontBBox [-92 -250 1010 922] \
+/ItalicAngle 0 /Ascent 752 /Descent -221 /CapHeight 737 /StemV 114 >>",
+];
+
+#[derive(Clone, Copy)]
+enum Align {
+    Left,
+    Right,
+}
+
+// 年度の収支・月ごとの収支・カテゴリー別の支出・大きな支出を前年度と比べた印刷用のPDF
+pub fn render(data: &[models::Item], config: &Config, year: i32) -> Vec<u8> {
+    let statistics = services::statistics::get_report(data, config);
+    let get_totals = |year: i32| {
+        statistics
+            .yearly
+            .periods
+            .iter()
+            .find(|period| period.period == year.to_string())
+            .map(|period| &period.totals)
+    };
+    let empty = Totals::default();
+    let current = get_totals(year).unwrap_or(&empty);
+    let previous = get_totals(year - 1).unwrap_or(&empty);
+    let format = |price: i64| config.currency.format(price);
+    let format_diff = |price: i64| services::summarize::format_price(price, &config.currency);
+
+    let mut document = Document::new();
+    document.title(&format!(
+        "{} 家計簿 年間レポート",
+        config.get_year_label(year)
+    ));
+    document.text_line(&format!("作成日: {}", Local::now().date_naive()));
+
+    document.heading("年間の収支");
+    let widths = [100.0, 130.0, 130.0, 135.0];
+    let aligns = [Align::Left, Align::Right, Align::Right, Align::Right];
+    document.table_row(
+        &widths,
+        &aligns,
+        &[
+            "",
+            &config.get_year_label(year),
+            &config.get_year_label(year - 1),
+            "増減",
+        ],
+        true,
+    );
+    for (label, price, previous_price) in [
+        ("収入", current.income as i32, previous.income as i32),
+        ("支出", current.expense as i32, previous.expense as i32),
+        ("収支", current.balance, previous.balance),
+    ] {
+        document.table_row(
+            &widths,
+            &aligns,
+            &[
+                label,
+                &format(price as i64),
+                &format(previous_price as i64),
+                &format_diff((price - previous_price) as i64),
+            ],
+            false,
+        );
+    }
+
+    document.heading("月ごとの収支");
+    document.table_row(&widths, &aligns, &["年月", "収入", "支出", "収支"], true);
+    let items: Vec<models::Item> = data
+        .iter()
+        .filter(|item| config.get_fiscal_year(item.get_date()) == year)
+        .cloned()
+        .collect();
+    let monthly = services::summarize::get_report(&items);
+    let first_month = NaiveDate::from_ymd_opt(year, config.fiscal_year_start, 1).unwrap();
+    for offset in 0..12 {
+        let month = first_month + Months::new(offset);
+        let period = month.format("%Y-%m").to_string();
+        let totals = monthly
+            .periods
+            .iter()
+            .find(|total| total.period == period)
+            .map(|total| &total.totals)
+            .unwrap_or(&empty);
+        document.table_row(
+            &widths,
+            &aligns,
+            &[
+                &format!("{}/{}", month.year(), month.month()),
+                &format(totals.income as i64),
+                &format(totals.expense as i64),
+                &format_diff(totals.balance as i64),
+            ],
+            false,
+        );
+    }
+
+    document.heading("カテゴリー別の支出");
+    let category_widths = [100.0, 100.0, 65.0, 100.0, 130.0];
+    let category_aligns = [
+        Align::Left,
+        Align::Right,
+        Align::Right,
+        Align::Right,
+        Align::Right,
+    ];
+    document.table_row(
+        &category_widths,
+        &category_aligns,
+        &["カテゴリー", "金額", "割合", "前年", "増減"],
+        true,
+    );
+    let mut categories: Vec<&CategoryTotal> = Vec::new();
+    for category in current.categories.iter().chain(&previous.categories) {
+        if category.category_type == "expense"
+            && categories.iter().all(|other| other.name != category.name)
+        {
+            categories.push(category);
+        }
+    }
+    for category in categories {
+        let get_amount = |totals: &Totals| {
+            totals
+                .categories
+                .iter()
+                .find(|other| other.category_type == "expense" && other.name == category.name)
+                .map_or(0, |other| other.amount as i32)
+        };
+        let price = get_amount(current);
+        let previous_price = get_amount(previous);
+        let ratio = if current.expense > 0 {
+            price as f64 * 100.0 / current.expense as f64
+        } else {
+            0.0
+        };
+        document.table_row(
+            &category_widths,
+            &category_aligns,
+            &[
+                &category.label,
+                &format(price as i64),
+                &format!("{:.1}%", ratio),
+                &format(previous_price as i64),
+                &format_diff((price - previous_price) as i64),
+            ],
+            false,
+        );
+    }
+
+    document.heading("大きな支出");
+    let expense_widths = [90.0, 240.0, 70.0, 95.0];
+    let expense_aligns = [Align::Left, Align::Left, Align::Left, Align::Right];
+    document.table_row(
+        &expense_widths,
+        &expense_aligns,
+        &["日付", "品目", "カテゴリー", "金額"],
+        true,
+    );
+    let mut expenses: Vec<&models::Item> = items
+        .iter()
+        .filter(|item| matches!(item.get_item_category(), models::Category::Expense(_)))
+        .collect();
+    expenses.sort_by_key(|item| std::cmp::Reverse(item.get_price()));
+    for item in expenses.iter().take(TOP_EXPENSE_COUNT) {
+        document.table_row(
+            &expense_widths,
+            &expense_aligns,
+            &[
+                &item.get_date().to_string(),
+                item.get_name(),
+                item.get_item_category().get_label(),
+                &format(item.get_price() as i64),
+            ],
+            false,
+        );
+    }
+
+    document.finish()
+}
+
+// ページごとのコンテンツストリームを組み立て、最後にPDFのオブジェクトと相互参照表を書き出す
+struct Document {
+    pages: Vec<String>,
+    content: String,
+    y: f32,
+}
+
+impl Document {
+    fn new() -> Self {
+        Document {
+            pages: Vec::new(),
+            content: String::new(),
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    // 残りの高さが足りなければ改ページする
+    fn reserve(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.pages.push(std::mem::take(&mut self.content));
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn title(&mut self, text: &str) {
+        self.reserve(30.0);
+        self.y -= 20.0;
+        self.text(MARGIN, self.y, 18.0, text);
+        self.y -= 10.0;
+    }
+
+    fn heading(&mut self, text: &str) {
+        // 見出しだけがページ末尾に残らないよう、数行分の余裕を確保する
+        self.reserve(30.0 + ROW_HEIGHT * 3.0);
+        self.y -= 24.0;
+        self.text(MARGIN, self.y, 13.0, text);
+        self.y -= 6.0;
+        self.line(MARGIN, self.y, PAGE_WIDTH - MARGIN, self.y);
+    }
+
+    fn text_line(&mut self, text: &str) {
+        self.reserve(ROW_HEIGHT);
+        self.y -= ROW_HEIGHT;
+        self.text(MARGIN, self.y, FONT_SIZE, text);
+    }
+
+    fn table_row(&mut self, widths: &[f32], aligns: &[Align], cells: &[&str], header: bool) {
+        self.reserve(ROW_HEIGHT);
+        self.y -= ROW_HEIGHT;
+        let mut x = MARGIN;
+        for ((width, align), cell) in widths.iter().zip(aligns).zip(cells) {
+            if cell.is_empty() {
+                x += width;
+                continue;
+            }
+            let cell = truncate(cell, width - 8.0);
+            let cell_x = match align {
+                Align::Left => x,
+                Align::Right => x + width - get_text_width(&cell, FONT_SIZE),
+            };
+            self.text(cell_x, self.y + 4.0, FONT_SIZE, &cell);
+            x += width;
+        }
+        if header {
+            self.line(MARGIN, self.y, x, self.y);
+        }
+    }
+
+    fn text(&mut self, x: f32, y: f32, size: f32, text: &str) {
+        self.content.push_str(&format!(
+            "BT /F1 {} Tf {:.1} {:.1} Td <{}> Tj ET\n",
+            size,
+            x,
+            y,
+            encode_text(text)
+        ));
+    }
+
+    fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32) {
+        self.content.push_str(&format!(
+            "0.5 w {:.1} {:.1} m {:.1} {:.1} l S\n",
+            x1, y1, x2, y2
+        ));
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.pages.push(self.content);
+        // 1: カタログ, 2: ページツリー, 3-5: フォント, 6以降: ページとコンテンツを交互に置く
+        let page_ids: Vec<usize> = (0..self.pages.len()).map(|index| 6 + index * 2).collect();
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                page_ids
+                    .iter()
+                    .map(|id| format!("{} 0 R", id))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                page_ids.len()
+            ),
+        ];
+        objects.extend(FONT_OBJECTS.iter().map(|object| object.to_string()));
+        for (page_id, content) in page_ids.iter().zip(&self.pages) {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+/Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                page_id + 1
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                content.len(),
+                content
+            ));
+        }
+
+        let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = Vec::new();
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", index + 1, object).as_bytes());
+        }
+        let xref_offset = pdf.len();
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            trailer.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        trailer.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        ));
+        pdf.extend_from_slice(trailer.as_bytes());
+        pdf
+    }
+}
+
+// UCS-2(UTF-16BE)の16進文字列。BMP外の文字は表示できないため"?"にする
+fn encode_text(text: &str) -> String {
+    text.chars()
+        .map(|char| {
+            let code = if (char as u32) <= 0xFFFF {
+                char as u32
+            } else {
+                '?' as u32
+            };
+            format!("{:04X}", code)
+        })
+        .collect()
+}
+
+// ASCIIは半角幅、それ以外は全角幅として計算する
+fn get_text_width(text: &str, size: f32) -> f32 {
+    text.chars()
+        .map(|char| if char.is_ascii() { 0.5 } else { 1.0 })
+        .sum::<f32>()
+        * size
+}
+
+fn truncate(text: &str, width: f32) -> String {
+    if get_text_width(text, FONT_SIZE) <= width {
+        return text.to_string();
+    }
+    let mut result = String::new();
+    for char in text.chars() {
+        if get_text_width(&format!("{}{}…", result, char), FONT_SIZE) > width {
+            break;
+        }
+        result.push(char);
+    }
+    result + "…"
+}
+
+#[cfg(test)]
+mod pdf_test {
+    use super::*;
+
+    fn get_test_item(
+        name: &str,
+        category: models::Category,
+        price: u32,
+        date: &str,
+    ) -> models::Item {
+        models::Item::new(
+            name.to_string(),
+            category,
+            price,
+            NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+        )
+    }
+
+    fn get_test_data() -> Vec<models::Item> {
+        let salary = models::Category::Income(models::IncomeCategory::Salary);
+        let food = models::Category::Expense(models::ExpenseCategory::Food);
+        vec![
+            get_test_item("給料", salary, 300000, "2024-08-25"),
+            get_test_item("スーパー", food, 40000, "2024-08-10"),
+            get_test_item("給料", salary, 300000, "2025-08-25"),
+            get_test_item("ノートPC", food, 150000, "2025-08-10"),
+        ]
+    }
+
+    #[test]
+    fn test_render() {
+        let pdf = render(&get_test_data(), &Config::default(), 2025);
+        // 2行目のコメントはバイナリのため、位置はバイト列で調べる
+        let find = |needle: &str| {
+            pdf.windows(needle.len())
+                .rposition(|window| window == needle.as_bytes())
+                .unwrap()
+        };
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(pdf.starts_with(b"%PDF-1.4\n"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains("/Count 1"));
+        assert!(text.contains(&encode_text("2025年 家計簿 年間レポート")));
+        assert!(text.contains(&encode_text("ノートPC")));
+        assert!(text.contains(&encode_text("+110000円")));
+
+        // 相互参照表の位置が各オブジェクトの先頭を指している
+        let xref_offset = find("xref\n0 8\n");
+        assert!(text.ends_with(&format!("startxref\n{}\n%%EOF\n", xref_offset)));
+        assert!(text.contains(&format!("{:010} 00000 n ", find("1 0 obj\n"))));
+        assert!(text.contains(&format!("{:010} 00000 n ", find("7 0 obj\n"))));
+    }
+
+    #[test]
+    fn test_document_page_break() {
+        let mut document = Document::new();
+        for index in 0..100 {
+            document.text_line(&index.to_string());
+        }
+        let pdf = document.finish();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.contains("/Count 3"));
+    }
+
+    #[test]
+    fn test_encode_text() {
+        assert_eq!(encode_text("A円"), "00415186");
+        assert_eq!(encode_text("😀"), "003F");
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("abc", 100.0), "abc");
+        assert_eq!(truncate("あいうえお", 30.0), "あい…");
+    }
+}
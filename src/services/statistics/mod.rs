@@ -1,19 +1,82 @@
+use crate::config::{Config, OutputFormat};
+use crate::services::report::Report;
 use crate::{models, services};
+use serde::Serialize;
 use std::collections::BTreeMap;
 
-pub fn run(file_path: &str) {
-    println!("統計情報を表示します");
-    let data = services::io::read_data_or_panic(&file_path);
-    show_yearly_statistics(&data);
-    show_monthly_statistics(&data);
+// yearlyは年度ごと、monthlyは年をまたいだ月(1-12)ごとの集計
+#[derive(Serialize, Debug, PartialEq)]
+pub struct StatisticsReport {
+    pub yearly: Report,
+    pub monthly: Report,
 }
 
-fn show_yearly_statistics(data: &Vec<models::Item>) {
+// 年度ごとの推移を出すため、アーカイブもすべて読み込む
+pub fn run(config: &Config, output_format: OutputFormat) {
+    let data = services::io::read_data_with_archives_or_panic(&config.ledger_path);
+    match output_format {
+        OutputFormat::Text => {
+            println!("統計情報を表示します");
+            show_yearly_statistics(&data, config);
+            show_monthly_statistics(&data, config);
+        }
+        OutputFormat::Json => {
+            let report = get_report(&data, config);
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&report).expect("JSONに変換できませんでした")
+            );
+        }
+    }
+}
+
+pub fn get_report(data: &[models::Item], config: &Config) -> StatisticsReport {
+    let mut yearly = BTreeMap::new();
+    let mut monthly = BTreeMap::new();
+    for item in data {
+        let year = config.get_fiscal_year(item.get_date());
+        yearly
+            .entry(year)
+            .or_insert_with(|| (year.to_string(), config.get_year_label(year), Vec::new()))
+            .2
+            .push(item);
+        let month = item.get_month();
+        monthly
+            .entry(month)
+            .or_insert_with(|| (month.to_string(), format!("{}月", month), Vec::new()))
+            .2
+            .push(item);
+    }
+    StatisticsReport {
+        yearly: Report::new(yearly),
+        monthly: Report::new(monthly),
+    }
+}
+
+fn show_yearly_statistics(data: &[models::Item], config: &Config) {
     println!("年ごとの統計情報");
+    for (year, price) in get_yearly_statistics(data, config) {
+        println!(
+            "{}: {}",
+            config.get_year_label(year),
+            config.currency.format(price as i64)
+        );
+    }
+}
+
+fn show_monthly_statistics(data: &[models::Item], config: &Config) {
+    println!("月ごとの統計情報");
+    for (month, price) in get_monthly_statistics(data) {
+        println!("{}月: {}", month, config.currency.format(price as i64));
+    }
+}
+
+// 年度ごとの収支
+pub fn get_yearly_statistics(data: &[models::Item], config: &Config) -> BTreeMap<i32, i32> {
     let mut yearly_statistics = BTreeMap::new();
 
     for item in data {
-        let year = item.get_year();
+        let year = config.get_fiscal_year(item.get_date());
         let price = item.get_price_for_summary();
 
         yearly_statistics
@@ -21,14 +84,11 @@ fn show_yearly_statistics(data: &Vec<models::Item>) {
             .and_modify(|sum| *sum += price)
             .or_insert(price);
     }
-
-    for (year, price) in yearly_statistics {
-        println!("{}年: {}", year, price);
-    }
+    yearly_statistics
 }
 
-fn show_monthly_statistics(data: &Vec<models::Item>) {
-    println!("月ごとの統計情報");
+// 年をまたいだ月(1-12)ごとの収支
+pub fn get_monthly_statistics(data: &[models::Item]) -> BTreeMap<u32, i32> {
     let mut monthly_statistics = BTreeMap::new();
 
     for item in data {
@@ -40,7 +100,5 @@ fn show_monthly_statistics(data: &Vec<models::Item>) {
             .and_modify(|sum| *sum += price)
             .or_insert(price);
     }
-    for (month, price) in monthly_statistics {
-        println!("{}月: {}", month, price);
-    }
+    monthly_statistics
 }
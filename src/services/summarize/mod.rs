@@ -2,29 +2,99 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use chrono::{Datelike, NaiveDate};
 
+use crate::config::{Config, CurrencyFormat, OutputFormat};
+use crate::services::report::Report;
 use crate::{models, services};
 
-pub fn run(file_path: &str) {
-    println!("家計簿の集計を行います");
-    let data = services::io::read_data_or_panic(file_path);
+// 月の集計の基準。Paymentではカードで支払った項目を支払日の月に計上する
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SummaryBasis {
+    Usage,
+    Payment,
+}
+
+impl SummaryBasis {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "usage" => Some(SummaryBasis::Usage),
+            "payment" => Some(SummaryBasis::Payment),
+            _ => None,
+        }
+    }
+}
+
+// 月ごとの推移を全期間で出すため、アーカイブもすべて読み込む
+pub fn run(config: &Config, output_format: OutputFormat, basis: SummaryBasis) {
+    let data = services::io::read_data_with_archives_or_panic(&config.ledger_path);
+    let data = match basis {
+        SummaryBasis::Usage => data,
+        SummaryBasis::Payment => services::card::to_payment_basis(
+            &data,
+            &services::account::read_accounts(&config.ledger_path),
+        ),
+    };
+    match output_format {
+        OutputFormat::Text => {
+            println!("家計簿の集計を行います");
+            print_table(get_monthly_table(&data), &config.currency);
+        }
+        OutputFormat::Json => println!("{}", get_report(&data).to_json()),
+    }
+}
 
-    let target_dates: BTreeSet<NaiveDate> = get_target_dates(&data);
+// 月ごとの集計。periodは"2025-08"の形式
+pub fn get_report(data: &[models::Item]) -> Report {
+    let mut groups = BTreeMap::new();
+    for item in data {
+        let date = item.get_first_day();
+        groups
+            .entry(date)
+            .or_insert_with(|| {
+                (
+                    date.format("%Y-%m").to_string(),
+                    format_date(date),
+                    Vec::new(),
+                )
+            })
+            .2
+            .push(item);
+    }
+    Report::new(groups)
+}
+
+// 月初の日付ごとの収支
+pub fn get_monthly_table(data: &[models::Item]) -> BTreeMap<NaiveDate, i32> {
+    let target_dates: BTreeSet<NaiveDate> = get_target_dates(data);
     let mut result_table: BTreeMap<NaiveDate, i32> = BTreeMap::new();
 
     for date in target_dates {
-        let filtered_data = get_filtered_data(&data, date);
+        let filtered_data = get_filtered_data(data, date);
         let sum = summarize_data(&filtered_data);
         result_table.insert(date, sum);
     }
-    print_table(result_table);
+    result_table
 }
 
-fn get_target_dates(data: &Vec<models::Item>) -> BTreeSet<NaiveDate> {
+// 月初の日付ごと・カテゴリーごとの金額
+pub fn get_category_table(
+    data: &[models::Item],
+) -> BTreeMap<NaiveDate, BTreeMap<models::Category, u32>> {
+    let mut table: BTreeMap<NaiveDate, BTreeMap<models::Category, u32>> = BTreeMap::new();
+    for item in data {
+        let prices = table.entry(item.get_first_day()).or_default();
+        for (category, price) in item.get_category_prices() {
+            *prices.entry(category).or_insert(0) += price;
+        }
+    }
+    table
+}
+
+fn get_target_dates(data: &[models::Item]) -> BTreeSet<NaiveDate> {
     let target_dates: BTreeSet<_> = data.iter().map(|item| item.get_first_day()).collect();
     target_dates
 }
 
-fn get_filtered_data(data: &Vec<models::Item>, filter_date: NaiveDate) -> Vec<&models::Item> {
+fn get_filtered_data(data: &[models::Item], filter_date: NaiveDate) -> Vec<&models::Item> {
     let filtered_data: Vec<&models::Item> = data
         .iter()
         .filter(|item| {
@@ -46,19 +116,19 @@ fn format_date(date: NaiveDate) -> String {
     format!("{}/{}", date.year(), date.month())
 }
 
-fn format_price(price: i32) -> String {
+pub fn format_price(price: i64, currency: &CurrencyFormat) -> String {
     if price > 0 {
-        format!("+{}", price)
+        format!("+{}", currency.format(price))
     } else {
-        format!("{}", price)
+        currency.format(price)
     }
 }
 
-fn print_table(result_table: BTreeMap<NaiveDate, i32>) {
+fn print_table(result_table: BTreeMap<NaiveDate, i32>, currency: &CurrencyFormat) {
     for result in result_table {
         let date = format_date(result.0);
-        let price = format_price(result.1);
-        println!("{}の収支は{}円でした", date, price);
+        let price = format_price(result.1 as i64, currency);
+        println!("{}の収支は{}でした", date, price);
     }
 }
 
@@ -154,6 +224,47 @@ mod summarize_test {
         assert_eq!(result, 0);
     }
 
+    #[test]
+    fn test_get_monthly_table() {
+        let result = get_monthly_table(&get_test_data());
+        assert_eq!(result.len(), 3);
+        assert_eq!(
+            result[&NaiveDate::from_ymd_opt(2022, 1, 1).unwrap()],
+            195000
+        );
+        assert_eq!(
+            result[&NaiveDate::from_ymd_opt(2022, 4, 1).unwrap()],
+            -10000
+        );
+    }
+
+    #[test]
+    fn test_get_category_table() {
+        let result = get_category_table(&get_test_data());
+        let january = &result[&NaiveDate::from_ymd_opt(2022, 1, 1).unwrap()];
+        assert_eq!(
+            january[&models::Category::Expense(models::ExpenseCategory::Food)],
+            5000
+        );
+        assert_eq!(
+            january[&models::Category::Expense(models::ExpenseCategory::Hobby)],
+            100000
+        );
+        assert_eq!(january.len(), 3);
+        assert_eq!(result.len(), 3);
+    }
+
+    #[test]
+    fn test_get_report() {
+        let report = get_report(&get_test_data());
+        assert_eq!(report.periods.len(), 3);
+        assert_eq!(report.periods[0].period, "2022-01");
+        assert_eq!(report.periods[0].label, "2022/1");
+        assert_eq!(report.periods[0].totals.balance, 195000);
+        assert_eq!(report.periods[0].totals.categories.len(), 3);
+        assert_eq!(report.totals.balance, 182000);
+    }
+
     #[test]
     fn test_format_date() {
         let date = NaiveDate::from_ymd_opt(2023, 5, 15).unwrap();
@@ -163,20 +274,20 @@ mod summarize_test {
 
     #[test]
     fn test_format_price_positive() {
-        let result = format_price(1000);
-        assert_eq!(result, "+1000");
+        let result = format_price(1000, &CurrencyFormat::default());
+        assert_eq!(result, "+1000円");
     }
 
     #[test]
     fn test_format_price_negative() {
-        let result = format_price(-1000);
-        assert_eq!(result, "-1000");
+        let result = format_price(-1000, &CurrencyFormat::default());
+        assert_eq!(result, "-1000円");
     }
 
     #[test]
     fn test_format_price_zero() {
-        let result = format_price(0);
-        assert_eq!(result, "0");
+        let result = format_price(0, &CurrencyFormat::default());
+        assert_eq!(result, "0円");
     }
 
     #[test]
@@ -188,6 +299,6 @@ mod summarize_test {
         // print_tableは標準出力に書き込むので、実際のテストでは
         // 出力をキャプチャする必要がありますが、ここでは関数が
         // パニックしないことを確認します
-        print_table(result_table);
+        print_table(result_table, &CurrencyFormat::default());
     }
 }
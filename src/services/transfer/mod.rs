@@ -0,0 +1,207 @@
+use crate::config::Config;
+use crate::services;
+use chrono::{Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+pub const TRANSFER_FILE: &str = "transfers.json";
+
+// 口座間の資金移動(ATMでの引き出し、カードの引き落とし、電子マネーのチャージなど)。
+// 収入でも支出でもないため家計簿の項目とは別に保存し、集計・統計には含めない
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Transfer {
+    id: u32,
+    date: NaiveDate,
+    from: String,
+    to: String,
+    amount: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    memo: Option<String>,
+}
+
+impl Transfer {
+    pub fn new(id: u32, date: NaiveDate, from: String, to: String, amount: u32) -> Self {
+        Transfer {
+            id,
+            date,
+            from,
+            to,
+            amount,
+            memo: None,
+        }
+    }
+
+    pub fn with_memo(mut self, memo: Option<String>) -> Self {
+        self.memo = memo;
+        self
+    }
+
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn get_date(&self) -> NaiveDate {
+        self.date
+    }
+
+    pub fn get_from(&self) -> &str {
+        &self.from
+    }
+
+    pub fn get_to(&self) -> &str {
+        &self.to
+    }
+
+    pub fn get_amount(&self) -> u32 {
+        self.amount
+    }
+
+    // 口座から見た増減。出金元は負、入金先は正、関係しない口座は0
+    pub fn get_amount_for(&self, account: &str) -> i64 {
+        if self.from == account {
+            -(self.amount as i64)
+        } else if self.to == account {
+            self.amount as i64
+        } else {
+            0
+        }
+    }
+
+    pub fn get_label(&self) -> String {
+        match &self.memo {
+            Some(memo) => format!("振替 {}→{} ({})", self.from, self.to, memo),
+            None => format!("振替 {}→{}", self.from, self.to),
+        }
+    }
+}
+
+pub fn run(
+    config: &Config,
+    from: &str,
+    to: &str,
+    amount: &str,
+    date: Option<&str>,
+    memo: Option<&str>,
+) {
+    if from == to {
+        panic!("振替元と振替先が同じです");
+    }
+    for name in [from, to] {
+        services::account::find_or_panic(&config.ledger_path, name);
+    }
+    let amount: u32 = amount.parse().expect("金額は数値で入力してください");
+    let date = date.map_or(Local::now().date_naive(), |date| {
+        NaiveDate::from_str(date).expect("日付はyyyy-mm-ddの形式で入力してください")
+    });
+    let mut transfers = read_transfers(&config.ledger_path);
+    let id = transfers
+        .iter()
+        .map(|transfer| transfer.id)
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let transfer = Transfer::new(id, date, from.to_string(), to.to_string(), amount)
+        .with_memo(memo.map(|memo| memo.to_string()));
+    println!(
+        "{} {} {}を登録しました",
+        date,
+        transfer.get_label(),
+        config.currency.format(amount as i64)
+    );
+    let message = format!(
+        "Transfer: {} {} {}",
+        transfer.get_label(),
+        config.currency.format(amount as i64),
+        date
+    );
+    transfers.push(transfer);
+    transfers.sort_by_key(|transfer| (transfer.date, transfer.id));
+    write_transfers(&transfers, config, &message);
+}
+
+pub fn run_list(config: &Config, month: NaiveDate) {
+    let transfers: Vec<Transfer> = read_transfers(&config.ledger_path)
+        .into_iter()
+        .filter(|transfer| {
+            transfer.date.year() == month.year() && transfer.date.month() == month.month()
+        })
+        .collect();
+    if transfers.is_empty() {
+        println!("{}年{}月の振替はありません", month.year(), month.month());
+        return;
+    }
+    println!("{}年{}月の振替", month.year(), month.month());
+    for transfer in transfers {
+        println!(
+            "ID{} {} {} {}",
+            transfer.id,
+            transfer.date,
+            transfer.get_label(),
+            config.currency.format(transfer.amount as i64)
+        );
+    }
+}
+
+pub fn read_transfers(file_path: &str) -> Vec<Transfer> {
+    services::io::read_json_or_default(&services::io::get_sibling_path(file_path, TRANSFER_FILE))
+}
+
+pub fn write_transfers(transfers: &[Transfer], config: &Config, message: &str) {
+    services::io::write_sidecar(&transfers, TRANSFER_FILE, config, message);
+}
+
+#[cfg(test)]
+mod transfer_test {
+    use super::*;
+    use std::fs;
+
+    fn get_test_transfer() -> Transfer {
+        Transfer::new(
+            1,
+            NaiveDate::from_ymd_opt(2025, 8, 5).unwrap(),
+            "普通預金".to_string(),
+            "現金".to_string(),
+            30000,
+        )
+    }
+
+    #[test]
+    fn test_get_amount_for() {
+        let transfer = get_test_transfer();
+        assert_eq!(transfer.get_amount_for("普通預金"), -30000);
+        assert_eq!(transfer.get_amount_for("現金"), 30000);
+        assert_eq!(transfer.get_amount_for("Suica"), 0);
+    }
+
+    #[test]
+    fn test_get_label() {
+        assert_eq!(get_test_transfer().get_label(), "振替 普通預金→現金");
+        assert_eq!(
+            get_test_transfer()
+                .with_memo(Some("ATM".to_string()))
+                .get_label(),
+            "振替 普通預金→現金 (ATM)"
+        );
+    }
+
+    #[test]
+    fn test_read_write_transfers() {
+        let test_dir = std::env::temp_dir().join("kakeibo_transfer_test");
+        fs::create_dir_all(&test_dir).unwrap();
+        let ledger_path = test_dir.join("data.json");
+        let mut config = Config {
+            ledger_path: ledger_path.to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+        config.backup.enabled = false;
+        assert!(read_transfers(&config.ledger_path).is_empty());
+
+        write_transfers(&[get_test_transfer()], &config, "Transfer");
+        assert_eq!(
+            read_transfers(&config.ledger_path),
+            vec![get_test_transfer()]
+        );
+
+        fs::remove_dir_all(&test_dir).unwrap();
+    }
+}
@@ -1,9 +1,12 @@
+use crate::config::EnabledCategories;
+use crate::models::Category;
+
 pub struct InputValidator {}
 
 impl InputValidator {
     pub fn validate_service_type(service_type: u8) {
         match service_type {
-            0 | 1 | 2 => {}
+            0..=2 => {}
             _ => panic!("入力値が不正です"),
         }
     }
@@ -15,17 +18,17 @@ impl InputValidator {
         }
     }
 
-    pub fn validate_category_type(register_type: u8, category_type: u8) {
-        if register_type == 0 {
-            match category_type {
-                0 | 1 | 2 => {}
-                _ => panic!("入力値が不正です"),
-            }
-        } else {
-            match category_type {
-                0 | 1 | 2 => {}
-                _ => panic!("入力値が不正です"),
-            }
+    pub fn validate_category_type(_register_type: u8, category_type: u8) {
+        // 収入・支出ともにカテゴリー種別は0から2
+        match category_type {
+            0..=2 => {}
+            _ => panic!("入力値が不正です"),
+        }
+    }
+
+    pub fn validate_enabled_category(category: &Category, enabled: &EnabledCategories) {
+        if !enabled.contains(category) {
+            panic!("無効化されているカテゴリーです");
         }
     }
 }
@@ -75,4 +78,26 @@ mod validate_test {
         InputValidator::validate_category_type(0, 3);
         InputValidator::validate_category_type(1, 3);
     }
+
+    #[test]
+    fn test_validate_enabled_category() {
+        let enabled = EnabledCategories::default();
+        InputValidator::validate_enabled_category(
+            &Category::Expense(crate::models::ExpenseCategory::Food),
+            &enabled,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "無効化されているカテゴリーです")]
+    fn test_validate_enabled_category_panic() {
+        let enabled = EnabledCategories {
+            income: vec![],
+            expense: vec![crate::models::ExpenseCategory::Food],
+        };
+        InputValidator::validate_enabled_category(
+            &Category::Expense(crate::models::ExpenseCategory::Hobby),
+            &enabled,
+        );
+    }
 }